@@ -1,3 +1,42 @@
+/// Every command the frontend can invoke. Listing them makes each one a permission, so a
+/// window can only call the commands its capability grants.
+const COMMANDS: &[&str] = &[
+    "get_clipboard_source",
+    "get_enabled",
+    "set_enabled",
+    "get_rules",
+    "set_rules",
+    "export_rules",
+    "preview_import",
+    "import_rules",
+    "lint_rules",
+    "get_rule_diagnostics",
+    "get_rules_error",
+    "get_config_error",
+    "get_policy_settings",
+    "get_block_strategy",
+    "set_block_strategy",
+    "get_active_rules",
+    "get_overrides",
+    "add_override",
+    "remove_override",
+    "get_pending_block",
+    "resolve_blocked_paste",
+    "get_paste_review",
+    "resolve_paste_review",
+    "evaluate_paste",
+    "list_apps",
+    "is_windows_platform",
+    "holds_pastes",
+    "app_detection_limit",
+    "check_accessibility",
+    "open_accessibility_settings",
+];
+
 fn main() {
-    tauri_build::build()
+    tauri_build::try_build(
+        tauri_build::Attributes::new()
+            .app_manifest(tauri_build::AppManifest::new().commands(COMMANDS)),
+    )
+    .expect("failed to run tauri-build");
 }
@@ -0,0 +1,56 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::rules;
+use crate::storage;
+
+/// Append-only event log in the app data dir, one JSON object per line
+pub const AUDIT_FILE: &str = "audit.jsonl";
+
+/// Past this size the log is moved to `audit.jsonl.1` and a new one started
+const MAX_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Unix seconds
+    pub ts: u64,
+    pub event: String,
+    #[serde(default)]
+    pub detail: Value,
+}
+
+/// Record an event. Failures are logged to stderr; auditing never stops the guard.
+pub fn record(app: &tauri::AppHandle, event: &str, detail: Value) {
+    let Some(path) = storage::data_path(app, AUDIT_FILE) else {
+        return;
+    };
+    if let Err(e) = append(&path, event, detail) {
+        eprintln!("audit: {}", e);
+    }
+}
+
+fn append(path: &Path, event: &str, detail: Value) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    if fs::metadata(path).is_ok_and(|m| m.len() > MAX_BYTES) {
+        fs::rename(path, storage::sibling(path, ".1")).map_err(|e| e.to_string())?;
+    }
+    let entry = AuditEntry {
+        ts: rules::unix_now(),
+        event: event.to_string(),
+        detail,
+    };
+    let mut line = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| f.write_all(line.as_bytes()))
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
@@ -0,0 +1,93 @@
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use tauri::tray::TrayIcon;
+use tauri::{AppHandle, Manager};
+
+use crate::audit;
+use crate::detectors;
+use crate::policy::Policy;
+use crate::rules::CopyContext;
+
+const TOOLTIP: &str = "Clipboard Guard";
+
+/// How long after this copy the clipboard should be cleared, if the policy asks for it.
+/// `content` is only read when the policy clears on secrets; a concealed copy counts as one
+/// without being read.
+pub fn delay(
+    policy: &Policy,
+    source_app_id: Option<&str>,
+    copy: &CopyContext,
+    content: impl FnOnce() -> Option<String>,
+) -> Option<Duration> {
+    let auto_clear = policy.auto_clear.as_ref()?;
+    let from_group = source_app_id.is_some_and(|id| {
+        auto_clear
+            .groups
+            .iter()
+            .any(|group| policy.in_group(group, id))
+    });
+    let has_secret = || {
+        copy.concealed
+            || content().is_some_and(|text| {
+                detectors::scan_with(&text, &policy.detectors)
+                    .iter()
+                    .any(|f| detectors::SECRET_DETECTORS.contains(&f.detector.as_str()))
+            })
+    };
+    (from_group || (auto_clear.secrets && has_secret()))
+        .then(|| Duration::from_secs(auto_clear.after_secs))
+}
+
+/// A pending clear, counted down in the tray tooltip
+pub struct Timer {
+    deadline: Instant,
+    source_app_id: Option<String>,
+    /// Seconds last shown in the tooltip
+    shown: Option<u64>,
+}
+
+impl Timer {
+    pub fn start(after: Duration, source_app_id: Option<String>) -> Self {
+        Self {
+            deadline: Instant::now() + after,
+            source_app_id,
+            shown: None,
+        }
+    }
+
+    /// Update the countdown; true once the clipboard is due to be cleared
+    pub fn tick(&mut self, app: &AppHandle) -> bool {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return true;
+        }
+        let secs = remaining.as_secs() + 1;
+        if self.shown != Some(secs) {
+            self.shown = Some(secs);
+            set_tooltip(app, &format!("{} — clipboard clears in {}s", TOOLTIP, secs));
+        }
+        false
+    }
+
+    /// The clipboard changed or the guard was turned off
+    pub fn cancel(self, app: &AppHandle) {
+        set_tooltip(app, TOOLTIP);
+    }
+
+    /// Called once the backend has cleared the clipboard
+    pub fn finish(self, app: &AppHandle) {
+        set_tooltip(app, TOOLTIP);
+        audit::record(
+            app,
+            "clipboard_cleared",
+            json!({ "source_app_id": self.source_app_id }),
+        );
+    }
+}
+
+fn set_tooltip(app: &AppHandle, text: &str) {
+    if let Some(tray) = app.try_state::<TrayIcon>() {
+        let _ = tray.set_tooltip(Some(text));
+    }
+}
@@ -0,0 +1,275 @@
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::audit;
+use crate::clipboard::{self, ClipboardState};
+use crate::policy;
+use crate::rules::{BlockRule, Evaluation, RuleAction};
+use crate::watcher::RulesReloaded;
+
+/// What the clipboard holds while a blocked app is in front, when blocking by replacement
+pub const PLACEHOLDER: &str = "Clipboard Guard blocked pasting this content here.";
+
+/// The paste that was most recently blocked, waiting for the user to decide
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PendingBlock {
+    pub source_app_id: Option<String>,
+    pub source_app_name: Option<String>,
+    pub dest_app_id: String,
+    pub dest_app_name: Option<String>,
+    pub rule_id: Option<String>,
+    /// Decided by the organization; only Keep Blocking is offered
+    #[serde(default)]
+    pub managed: bool,
+}
+
+/// Whether a block comes from the organization: a managed rule, or the trust zones when
+/// no rule matched. Users can't lift those.
+pub fn by_policy(evaluation: &Evaluation, rules: &[BlockRule]) -> bool {
+    evaluation.matched.is_none_or(|i| rules[i].managed)
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockChoice {
+    /// Let exactly one paste through
+    AllowOnce,
+    /// Add an Allow rule for the pair
+    AlwaysAllow,
+    KeepBlocking,
+}
+
+/// Buttons on the blocked-paste notification, where the platform can show them
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+const CHOICES: [(BlockChoice, &str, &str); 3] = [
+    (BlockChoice::AllowOnce, "allow_once", "Allow once"),
+    (
+        BlockChoice::AlwaysAllow,
+        "always_allow",
+        "Always allow this pair",
+    ),
+    (BlockChoice::KeepBlocking, "keep_blocking", "Keep blocking"),
+];
+
+/// Tray items offering the choices. They stay available after the notification is
+/// gone, and are the only way to choose on macOS, whose notifications can't carry
+/// buttons for an unsigned app.
+pub struct BlockMenu(pub Vec<(BlockChoice, tauri::menu::MenuItem<tauri::Wry>)>);
+
+/// Enable the tray items that apply to `pending`, or none without one
+fn set_menu_enabled(app: &AppHandle, pending: Option<&PendingBlock>) {
+    if let Some(menu) = app.try_state::<BlockMenu>() {
+        for (choice, item) in &menu.0 {
+            let _ = item.set_enabled(pending.is_some_and(|p| p.allows(*choice)));
+        }
+    }
+}
+
+impl PendingBlock {
+    fn allows(&self, choice: BlockChoice) -> bool {
+        !self.managed || choice == BlockChoice::KeepBlocking
+    }
+}
+
+/// Notification text for a blocked paste
+pub fn message(managed: bool, src_name: &str, dst_name: &str) -> String {
+    if managed {
+        format!(
+            "Paste blocked: {} → {}. Your organization doesn't allow this paste.",
+            src_name, dst_name
+        )
+    } else {
+        format!(
+            "Paste blocked: {} → {}. Use the Clipboard Guard menu to allow it.",
+            src_name, dst_name
+        )
+    }
+}
+
+/// Record a blocked paste and offer the choices for it
+pub fn offer(app: &AppHandle, state: &Arc<Mutex<ClipboardState>>, pending: PendingBlock) {
+    if let Ok(mut s) = state.lock() {
+        s.pending_block = Some(pending.clone());
+    }
+    set_menu_enabled(app, Some(&pending));
+    let _ = app.emit("pending-block-changed", Some(&pending));
+}
+
+/// Tell the user about the pending block, with its choices as notification buttons
+/// where the platform supports them
+pub fn notify(app: &AppHandle, state: &Arc<Mutex<ClipboardState>>, body: &str) {
+    let Some(pending) = state.lock().ok().and_then(|s| s.pending_block.clone()) else {
+        return;
+    };
+    // Keep Blocking alone isn't worth a button
+    if pending.managed {
+        show_plain(app, body);
+        return;
+    }
+    let (app_for_answer, state) = (app.clone(), state.clone());
+    let answer = move |choice: BlockChoice| {
+        // A newer block or a clipboard change may have replaced the one asked about
+        let current = state.lock().ok().and_then(|s| s.pending_block.clone());
+        if current.as_ref() != Some(&pending) {
+            return;
+        }
+        if let Err(e) = resolve(&app_for_answer, &state, choice) {
+            eprintln!("blocked: {}", e);
+        }
+    };
+    if let Err(e) = show_choices(app, body, answer) {
+        eprintln!("blocked: {}", e);
+        show_plain(app, body);
+    }
+}
+
+fn show_plain(app: &AppHandle, body: &str) {
+    use tauri_plugin_notification::NotificationExt;
+    let _ = app
+        .notification()
+        .builder()
+        .title("Clipboard Guard")
+        .body(body)
+        .show();
+}
+
+#[cfg(target_os = "linux")]
+fn show_choices(
+    _app: &AppHandle,
+    body: &str,
+    answer: impl FnOnce(BlockChoice) + Send + 'static,
+) -> Result<(), String> {
+    let mut notification = notify_rust::Notification::new();
+    notification
+        .summary("Clipboard Guard")
+        .body(body)
+        .auto_icon();
+    for (_, id, label) in CHOICES {
+        notification.action(id, label);
+    }
+    let handle = notification.show().map_err(|e| e.to_string())?;
+    // Returns once a button is clicked or the notification is closed
+    std::thread::spawn(move || {
+        handle.wait_for_action(|id| {
+            if let Some((choice, _, _)) = CHOICES.iter().find(|(_, c, _)| *c == id) {
+                answer(*choice);
+            }
+        })
+    });
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn show_choices(
+    app: &AppHandle,
+    body: &str,
+    answer: impl FnOnce(BlockChoice) + Send + 'static,
+) -> Result<(), String> {
+    use tauri_winrt_notification::Toast;
+    // Toasts need an installed app's id to show; a dev build borrows PowerShell's
+    let app_id = if tauri::is_dev() {
+        Toast::POWERSHELL_APP_ID.to_string()
+    } else {
+        app.config().identifier.clone()
+    };
+    let mut answer = Some(answer);
+    CHOICES
+        .iter()
+        .fold(Toast::new(&app_id), |toast, (_, id, label)| {
+            toast.add_button(label, id)
+        })
+        .title("Clipboard Guard")
+        .text1(body)
+        .on_activated(move |id| {
+            let choice = CHOICES.iter().find(|(_, c, _)| Some(*c) == id.as_deref());
+            if let (Some((choice, _, _)), Some(answer)) = (choice, answer.take()) {
+                answer(*choice);
+            }
+            Ok(())
+        })
+        .show()
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn show_choices(
+    app: &AppHandle,
+    body: &str,
+    _answer: impl FnOnce(BlockChoice) + Send + 'static,
+) -> Result<(), String> {
+    show_plain(app, body);
+    Ok(())
+}
+
+/// Drop the pending block, e.g. when the clipboard changes
+pub fn withdraw(app: &AppHandle, state: &Arc<Mutex<ClipboardState>>) {
+    let had_pending = state
+        .lock()
+        .ok()
+        .and_then(|mut s| s.pending_block.take())
+        .is_some();
+    if had_pending {
+        set_menu_enabled(app, None);
+        let _ = app.emit("pending-block-changed", None::<PendingBlock>);
+    }
+}
+
+/// Apply the user's choice for the pending block
+pub fn resolve(
+    app: &AppHandle,
+    state: &Arc<Mutex<ClipboardState>>,
+    choice: BlockChoice,
+) -> Result<(), String> {
+    let Some(pending) = state.lock().ok().and_then(|s| s.pending_block.clone()) else {
+        return Err("No blocked paste is waiting".into());
+    };
+    if !pending.allows(choice) {
+        return Err("Your organization blocked this paste; it can't be allowed".into());
+    }
+
+    match choice {
+        BlockChoice::AllowOnce => clipboard::allow_once(),
+        BlockChoice::AlwaysAllow => {
+            let diagnostics = crate::commit_rules(app, state, |s| {
+                if s.policy.settings.lock_user_rules {
+                    return Err("Your organization doesn't allow editing rules".into());
+                }
+                let allow = BlockRule {
+                    from_app_name: pending.source_app_name.clone(),
+                    to_app_name: pending.dest_app_name.clone(),
+                    ..BlockRule::new(
+                        pending.source_app_id.clone(),
+                        Some(pending.dest_app_id.clone()),
+                        RuleAction::Allow,
+                    )
+                };
+                // First among the user's rules so it wins over the one that blocked
+                let updated = std::iter::once(allow)
+                    .chain(policy::user_rules(&s.rules))
+                    .collect();
+                Ok(Some((s.policy.clone(), updated)))
+            })?
+            .unwrap_or_default();
+            let _ = app.emit(
+                "rules-reloaded",
+                RulesReloaded {
+                    applied: true,
+                    error: None,
+                    diagnostics,
+                },
+            );
+        }
+        BlockChoice::KeepBlocking => {}
+    }
+
+    audit::record(
+        app,
+        "blocked_paste_choice",
+        json!({ "choice": choice, "paste": pending }),
+    );
+    withdraw(app, state);
+    Ok(())
+}
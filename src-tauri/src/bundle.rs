@@ -0,0 +1,343 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::detectors::DetectorConfig;
+use crate::policy::{self, AutoClear, Labels, Policy, PolicySettings, StalePaste, TrustZones};
+use crate::rules::BlockRule;
+use crate::storage;
+
+/// Last bundle that verified, kept in the app data dir
+pub const CACHE_FILE: &str = "policy-bundle.json";
+
+/// Highest bundle version ever accepted, next to the cache. Only ever raised, so losing or
+/// tampering with the cache doesn't let an older bundle back in.
+pub const FLOOR_FILE: &str = "policy-bundle.floor";
+
+/// A policy bundle as distributed: base64 JSON payload plus an Ed25519 signature over its bytes
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SignedBundle {
+    pub payload: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BundlePayload {
+    /// Must increase with every release; older bundles are refused
+    pub version: u64,
+    #[serde(default)]
+    pub rules: Vec<BlockRule>,
+    #[serde(default)]
+    pub app_groups: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub detectors: DetectorConfig,
+    #[serde(default)]
+    pub settings: PolicySettings,
+    #[serde(default)]
+    pub auto_clear: Option<AutoClear>,
+    #[serde(default)]
+    pub labels: Option<Labels>,
+    #[serde(default)]
+    pub trust_zones: Option<TrustZones>,
+    #[serde(default)]
+    pub stale_paste: Option<StalePaste>,
+}
+
+/// Policy to enforce, plus a message when a bundle was refused
+pub struct Enforced {
+    pub policy: Policy,
+    pub alert: Option<String>,
+}
+
+/// Locally distributed bundle, next to policy.json
+pub fn bundle_path() -> PathBuf {
+    policy::policy_path().with_file_name("policy.bundle.json")
+}
+
+fn parse_key(encoded: &str) -> Option<VerifyingKey> {
+    let bytes: [u8; 32] = BASE64.decode(encoded.trim()).ok()?.try_into().ok()?;
+    VerifyingKey::from_bytes(&bytes).ok()
+}
+
+/// Check the signature against the pinned keys and decode the payload
+pub fn verify(bundle: &SignedBundle, trusted_keys: &[String]) -> Result<BundlePayload, String> {
+    let payload = BASE64
+        .decode(bundle.payload.trim())
+        .map_err(|e| format!("payload is not base64: {}", e))?;
+    let signature: [u8; 64] = BASE64
+        .decode(bundle.signature.trim())
+        .map_err(|e| format!("signature is not base64: {}", e))?
+        .try_into()
+        .map_err(|_| "signature must be 64 bytes".to_string())?;
+    let signature = Signature::from_bytes(&signature);
+
+    let trusted = trusted_keys
+        .iter()
+        .filter_map(|k| parse_key(k))
+        .any(|key| key.verify_strict(&payload, &signature).is_ok());
+    if !trusted {
+        return Err("signature does not match any trusted key".into());
+    }
+    serde_json::from_slice(&payload).map_err(|e| format!("invalid payload: {}", e))
+}
+
+fn read_bundle(path: &Path) -> Result<Option<SignedBundle>, String> {
+    match fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Could not read {}: {}", path.display(), e)),
+    }
+}
+
+/// The cached bundle, re-verified so a tampered cache is ignored
+pub fn last_good(cache_path: &Path, trusted_keys: &[String]) -> Option<BundlePayload> {
+    let cached = read_bundle(cache_path).ok()??;
+    verify(&cached, trusted_keys).ok()
+}
+
+fn floor_path(cache_path: &Path) -> PathBuf {
+    cache_path.with_file_name(FLOOR_FILE)
+}
+
+/// The lowest version still accepted: the recorded high-water mark or the cached bundle's
+pub fn floor(cache_path: &Path, current: Option<&BundlePayload>) -> u64 {
+    let recorded = fs::read_to_string(floor_path(cache_path))
+        .ok()
+        .and_then(|text| text.trim().parse().ok())
+        .unwrap_or(0);
+    recorded.max(current.map_or(0, |c| c.version))
+}
+
+/// Verify `candidate` against the pinned keys and the bundle in force. Refuses bundles
+/// below `floor`, and a reused version number with different contents.
+pub fn check(
+    candidate: &SignedBundle,
+    current: Option<&BundlePayload>,
+    floor: u64,
+    trusted_keys: &[String],
+) -> Result<BundlePayload, String> {
+    let payload = verify(candidate, trusted_keys)?;
+    if payload.version < floor {
+        return Err(format!(
+            "bundle version {} is older than installed version {}",
+            payload.version, floor
+        ));
+    }
+    if let Some(current) = current {
+        if payload.version == current.version && payload != *current {
+            return Err(format!(
+                "bundle version {} was reused with different contents",
+                payload.version
+            ));
+        }
+    }
+    Ok(payload)
+}
+
+/// `check` `candidate` against the cached bundle and make it the last good one
+pub fn accept(
+    candidate: &SignedBundle,
+    cache_path: &Path,
+    trusted_keys: &[String],
+) -> Result<BundlePayload, String> {
+    let current = last_good(cache_path, trusted_keys);
+    let floor = floor(cache_path, current.as_ref());
+    let payload = check(candidate, current.as_ref(), floor, trusted_keys)?;
+    if current.is_none_or(|c| c.version < payload.version) {
+        let json = serde_json::to_string_pretty(candidate).map_err(|e| e.to_string())?;
+        storage::write_atomic(cache_path, json.as_bytes())?;
+    }
+    if payload.version > floor {
+        storage::write_atomic(
+            &floor_path(cache_path),
+            payload.version.to_string().as_bytes(),
+        )?;
+    }
+    Ok(payload)
+}
+
+/// Layer a verified bundle over the base policy.json. Bundle rules come first;
+/// locks from either side apply, and a block strategy or auto-clear set by the bundle wins.
+pub fn apply(base: &Policy, payload: &BundlePayload) -> Policy {
+    let mut app_groups = base.app_groups.clone();
+    app_groups.extend(payload.app_groups.clone());
+    let mut disabled = base.detectors.disabled.clone();
+    disabled.extend(payload.detectors.disabled.iter().cloned());
+    Policy {
+        rules: payload
+            .rules
+            .iter()
+            .chain(base.rules.iter())
+            .cloned()
+            .collect(),
+        settings: PolicySettings {
+            lock_enabled: base.settings.lock_enabled || payload.settings.lock_enabled,
+            lock_user_rules: base.settings.lock_user_rules || payload.settings.lock_user_rules,
+            block_strategy: payload
+                .settings
+                .block_strategy
+                .or(base.settings.block_strategy),
+        },
+        trusted_keys: base.trusted_keys.clone(),
+        remote: base.remote.clone(),
+        auto_clear: payload.auto_clear.clone().or(base.auto_clear.clone()),
+        labels: payload.labels.clone().or(base.labels.clone()),
+        trust_zones: payload.trust_zones.clone().or(base.trust_zones.clone()),
+        stale_paste: payload.stale_paste.clone().or(base.stale_paste.clone()),
+        app_groups,
+        detectors: DetectorConfig { disabled },
+        bundle_version: Some(payload.version),
+    }
+}
+
+/// Resolve the policy to enforce. Bundles are only considered when policy.json pins at
+/// least one key; a bundle that fails verification leaves the last good one in force.
+pub fn enforce(base: Policy, cache_path: &Path) -> Enforced {
+    resolve(base, cache_path, accept)
+}
+
+/// `enforce` without updating the cache, for readers that don't own it (the CLI)
+pub fn enforce_read_only(base: Policy, cache_path: &Path) -> Enforced {
+    resolve(base, cache_path, |candidate, cache_path, keys| {
+        let current = last_good(cache_path, keys);
+        let floor = floor(cache_path, current.as_ref());
+        check(candidate, current.as_ref(), floor, keys)
+    })
+}
+
+fn resolve(
+    base: Policy,
+    cache_path: &Path,
+    accept: impl Fn(&SignedBundle, &Path, &[String]) -> Result<BundlePayload, String>,
+) -> Enforced {
+    if base.trusted_keys.is_empty() {
+        return Enforced {
+            policy: base,
+            alert: None,
+        };
+    }
+    let keys = base.trusted_keys.clone();
+    let (payload, alert) = match read_bundle(&bundle_path()) {
+        Ok(Some(candidate)) => match accept(&candidate, cache_path, &keys) {
+            Ok(payload) => (Some(payload), None),
+            Err(e) => {
+                let last = last_good(cache_path, &keys);
+                // A local bundle superseded by a newer fetched one is expected, not an attack
+                let superseded = matches!(
+                    (&last, verify(&candidate, &keys)),
+                    (Some(last), Ok(local)) if local.version < last.version
+                );
+                (last, (!superseded).then_some(e))
+            }
+        },
+        Ok(None) => (last_good(cache_path, &keys), None),
+        Err(e) => (last_good(cache_path, &keys), Some(e)),
+    };
+    Enforced {
+        policy: payload.map_or_else(|| base.clone(), |p| apply(&base, &p)),
+        alert,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn sign(key: &SigningKey, payload: &str) -> SignedBundle {
+        SignedBundle {
+            payload: BASE64.encode(payload),
+            signature: BASE64.encode(key.sign(payload.as_bytes()).to_bytes()),
+        }
+    }
+
+    fn trusted(key: &SigningKey) -> Vec<String> {
+        vec![BASE64.encode(key.verifying_key().to_bytes())]
+    }
+
+    fn cache_path(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("clipguard-bundle-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join(CACHE_FILE)
+    }
+
+    #[test]
+    fn refuses_a_key_that_is_not_pinned() {
+        let signer = SigningKey::from_bytes(&[1u8; 32]);
+        let other = SigningKey::from_bytes(&[2u8; 32]);
+        let bundle = sign(&signer, r#"{"version":1}"#);
+        assert!(verify(&bundle, &trusted(&other)).is_err());
+        assert_eq!(verify(&bundle, &trusted(&signer)).unwrap().version, 1);
+    }
+
+    #[test]
+    fn refuses_a_tampered_payload() {
+        let key = SigningKey::from_bytes(&[1u8; 32]);
+        let mut bundle = sign(&key, r#"{"version":1}"#);
+        bundle.payload = BASE64.encode(r#"{"version":9}"#);
+        assert!(verify(&bundle, &trusted(&key)).is_err());
+    }
+
+    #[test]
+    fn refuses_rollback_and_reused_versions() {
+        let key = SigningKey::from_bytes(&[1u8; 32]);
+        let keys = trusted(&key);
+        let cache = cache_path("versions");
+        accept(&sign(&key, r#"{"version":2}"#), &cache, &keys).unwrap();
+
+        let older = accept(&sign(&key, r#"{"version":1}"#), &cache, &keys);
+        assert!(older.unwrap_err().contains("older"));
+        let reused = r#"{"version":2,"settings":{"lock_enabled":true}}"#;
+        let reused = accept(&sign(&key, reused), &cache, &keys);
+        assert!(reused.unwrap_err().contains("reused"));
+        // The same bundle again is fine, and a newer one replaces it
+        accept(&sign(&key, r#"{"version":2}"#), &cache, &keys).unwrap();
+        accept(&sign(&key, r#"{"version":3}"#), &cache, &keys).unwrap();
+        assert_eq!(last_good(&cache, &keys).map(|p| p.version), Some(3));
+    }
+
+    #[test]
+    fn tampered_or_missing_cache_keeps_the_floor() {
+        let key = SigningKey::from_bytes(&[1u8; 32]);
+        let keys = trusted(&key);
+        let cache = cache_path("tampered");
+        accept(&sign(&key, r#"{"version":5}"#), &cache, &keys).unwrap();
+
+        let mut cached: SignedBundle =
+            serde_json::from_str(&fs::read_to_string(&cache).unwrap()).unwrap();
+        cached.payload = BASE64.encode(r#"{"version":6}"#);
+        fs::write(&cache, serde_json::to_string(&cached).unwrap()).unwrap();
+        assert_eq!(last_good(&cache, &keys), None);
+        let older = accept(&sign(&key, r#"{"version":1}"#), &cache, &keys);
+        assert!(older.unwrap_err().contains("older"));
+
+        fs::remove_file(&cache).unwrap();
+        let older = accept(&sign(&key, r#"{"version":4}"#), &cache, &keys);
+        assert!(older.unwrap_err().contains("older"));
+        // The bundle at the floor is taken again and re-cached
+        accept(&sign(&key, r#"{"version":5}"#), &cache, &keys).unwrap();
+        assert_eq!(last_good(&cache, &keys).map(|p| p.version), Some(5));
+    }
+
+    #[test]
+    fn floor_is_only_raised() {
+        let key = SigningKey::from_bytes(&[1u8; 32]);
+        let keys = trusted(&key);
+        let cache = cache_path("floor");
+        accept(&sign(&key, r#"{"version":3}"#), &cache, &keys).unwrap();
+        fs::remove_file(&cache).unwrap();
+        accept(&sign(&key, r#"{"version":3}"#), &cache, &keys).unwrap();
+        assert_eq!(floor(&cache, None), 3);
+        accept(&sign(&key, r#"{"version":7}"#), &cache, &keys).unwrap();
+        assert_eq!(floor(&cache, None), 7);
+    }
+}
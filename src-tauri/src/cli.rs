@@ -0,0 +1,194 @@
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::bundle;
+use crate::detectors::{self, Severity};
+use crate::overrides;
+use crate::policy::{self, Policy};
+use crate::rules::{self, RuleAction};
+
+/// Must match `identifier` in tauri.conf.json so the CLI reads the same rules as the app.
+const APP_IDENTIFIER: &str = "com.jackmcpickle.clipboard-guard";
+
+// Exit codes understood by the shell hooks
+const EXIT_CLEAN: i32 = 0;
+const EXIT_WARN: i32 = 1;
+const EXIT_CONFIRM: i32 = 2;
+const EXIT_BLOCK: i32 = 3;
+const EXIT_USAGE: i32 = 64;
+
+const ZSH_HOOK: &str = include_str!("shell/clipguard.zsh");
+const BASH_HOOK: &str = include_str!("shell/clipguard.bash");
+const FISH_HOOK: &str = include_str!("shell/clipguard.fish");
+
+/// Handle CLI subcommands. Returns `None` when the app should start normally.
+pub fn run(args: &[String]) -> Option<i32> {
+    let code = match args.get(1).map(String::as_str) {
+        Some("shell-init") => shell_init(args.get(2).map(String::as_str)),
+        Some("inspect") => inspect(&args[2..]),
+        _ => return None,
+    };
+    Some(code)
+}
+
+fn shell_init(shell: Option<&str>) -> i32 {
+    let template = match shell {
+        Some("zsh") => ZSH_HOOK,
+        Some("bash") => BASH_HOOK,
+        Some("fish") => FISH_HOOK,
+        _ => {
+            eprintln!("usage: clipguard shell-init zsh|bash|fish");
+            return EXIT_USAGE;
+        }
+    };
+    let exe = std::env::current_exe()
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| "clipguard".into());
+    let script = template.replace("@CLIPGUARD@", &shell_quote(&exe));
+    let _ = io::stdout().write_all(script.as_bytes());
+    EXIT_CLEAN
+}
+
+/// Read pasted text from stdin, print findings to stderr and report the verdict as the exit code.
+fn inspect(args: &[String]) -> i32 {
+    let mut dest: Option<&str> = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--dest" => dest = iter.next().map(String::as_str).filter(|d| !d.is_empty()),
+            _ => {
+                eprintln!("usage: clipguard inspect [--dest <app id>] < pasted-text");
+                return EXIT_USAGE;
+            }
+        }
+    }
+
+    let mut content = String::new();
+    if io::stdin().read_to_string(&mut content).is_err() {
+        // Not valid UTF-8 — binary data in a terminal paste is always suspicious
+        eprintln!("clipguard: pasted data is not valid text");
+        return EXIT_CONFIRM;
+    }
+
+    let dest = dest.map(str::to_string).or_else(terminal_app_id);
+    let dir = app_data_dir();
+    let managed = load_policy(dir.as_deref());
+    let findings = detectors::scan_with(&content, &managed.detectors);
+    let decision = match (dest, &dir) {
+        (Some(dest), Some(dir)) => {
+            let loaded = rules::load_from_dir(dir).unwrap_or_else(|e| {
+                eprintln!("clipguard: {}", e);
+                rules::default_rules()
+            });
+            let merged = policy::merge(&managed, loaded);
+            let overrides = overrides::load_from_dir(dir);
+            rules::evaluate_paste(
+                &merged,
+                &overrides,
+                None,
+                &dest,
+                &Default::default(),
+                None,
+                &managed,
+            )
+            .decision
+        }
+        _ => None,
+    };
+
+    let mut stderr = io::stderr().lock();
+    for f in &findings {
+        let _ = writeln!(stderr, "clipguard: [{:?}] {}", f.severity, f.message);
+    }
+
+    match decision {
+        Some(RuleAction::Block) => {
+            let _ = writeln!(stderr, "clipguard: paste blocked by policy");
+            EXIT_BLOCK
+        }
+        Some(RuleAction::Confirm) => {
+            let _ = writeln!(stderr, "clipguard: policy asks you to confirm this paste");
+            EXIT_CONFIRM
+        }
+        _ if detectors::max_severity(&findings) == Some(Severity::High) => EXIT_CONFIRM,
+        Some(RuleAction::Notify) => {
+            let _ = writeln!(stderr, "clipguard: policy asks you to check this paste");
+            EXIT_WARN
+        }
+        // Sanitize and Redact rewrite the system clipboard, which the shell hook doesn't read
+        None | Some(RuleAction::Allow | RuleAction::Sanitize | RuleAction::Redact)
+            if !findings.is_empty() =>
+        {
+            EXIT_WARN
+        }
+        None | Some(RuleAction::Allow | RuleAction::Sanitize | RuleAction::Redact) => EXIT_CLEAN,
+    }
+}
+
+/// Managed policy with any signed bundle applied, as the app would enforce it
+fn load_policy(dir: Option<&Path>) -> Policy {
+    let last_good = dir.map(|d| d.join(policy::LAST_GOOD_FILE));
+    let (base, error) = policy::load(last_good.as_deref());
+    if let Some(e) = error {
+        eprintln!("clipguard: {}", e);
+    }
+    let Some(dir) = dir else {
+        return base;
+    };
+    let enforced = bundle::enforce_read_only(base, &dir.join(bundle::CACHE_FILE));
+    if let Some(alert) = enforced.alert {
+        eprintln!("clipguard: policy bundle rejected: {}", alert);
+    }
+    enforced.policy
+}
+
+/// App id of the terminal the shell runs in, when the hook couldn't pass one: the
+/// executable of the nearest ancestor without a controlling terminal, named the way
+/// the desktop monitor names apps. Only Linux can be walked; elsewhere it's `None`
+/// and only the detectors apply.
+#[cfg(target_os = "linux")]
+fn terminal_app_id() -> Option<String> {
+    let mut pid = std::os::unix::process::parent_id();
+    while pid > 1 {
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        // The process name is parenthesized and may itself contain spaces or parentheses
+        let (_, fields) = stat.rsplit_once(')')?;
+        let fields: Vec<&str> = fields.split_whitespace().collect();
+        let (ppid, tty) = (fields.get(1)?.parse().ok()?, fields.get(4)?);
+        if *tty == "0" {
+            let name = std::fs::read_to_string(format!("/proc/{}/comm", pid))
+                .ok()
+                .map(|s| s.trim().to_string());
+            let exe = std::fs::read_link(format!("/proc/{}/exe", pid))
+                .ok()
+                .and_then(|p| p.file_name().map(|f| f.to_string_lossy().into_owned()));
+            return exe.or(name);
+        }
+        pid = ppid;
+    }
+    None
+}
+
+#[cfg(not(target_os = "linux"))]
+fn terminal_app_id() -> Option<String> {
+    None
+}
+
+/// Tauri's `app_data_dir`, resolved without a running app
+fn app_data_dir() -> Option<PathBuf> {
+    let base = if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|h| PathBuf::from(h).join("Library/Application Support"))
+    } else if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .filter(|d| !d.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")))
+    };
+    base.map(|b| b.join(APP_IDENTIFIER))
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
@@ -1,17 +1,36 @@
 use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
-
-use objc2_app_kit::{NSPasteboard, NSWorkspace};
+use std::time::{Duration, Instant};
+
+use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
+use objc2_app_kit::{
+    NSApplicationActivationOptions, NSPasteboard, NSPasteboardItem, NSPasteboardTypeString,
+    NSPasteboardWriting, NSRunningApplication, NSWorkspace,
+};
+use objc2_foundation::{NSArray, NSData, NSString};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter};
 use tauri_plugin_notification::NotificationExt;
 
-use crate::rules::{self, BlockRule, RuleAction};
+use crate::config::{self, BlockStrategy, Config};
+use crate::lineage::Lineage;
+use crate::overrides::Override;
+use crate::policy::Policy;
+use crate::review::{self, PasteReview};
+use crate::rules::{self, BlockRule, RuleAction, RuleDiagnostic};
+use crate::sanitize;
+use crate::{audit, autoclear, detectors, snapshot};
 
 const POLL_INTERVAL_MS: u64 = 300;
+/// How long an allowed paste may keep reading the original before the placeholder is back
+const ONCE_GRACE_MS: u64 = 500;
+/// The longest an allowed paste is served when the paste itself can't be seen (menu paste,
+/// or no Accessibility for the tap)
+const ONCE_WINDOW_SECS: u64 = 10;
 
 // --- CGEventTap FFI ---
 
@@ -28,6 +47,7 @@ type CGEventTapCallBack = extern "C" fn(
     user_info: *mut c_void,
 ) -> CGEventRef;
 
+const CG_HID_EVENT_TAP: u32 = 0;
 const CG_SESSION_EVENT_TAP: u32 = 1;
 const CG_HEAD_INSERT_EVENT_TAP: u32 = 0;
 const CG_EVENT_TAP_OPTION_DEFAULT: u32 = 0;
@@ -50,6 +70,13 @@ extern "C" {
     fn CFMachPortInvalidate(tap: CFMachPortRef);
     fn CGEventGetFlags(event: CGEventRef) -> u64;
     fn CGEventGetIntegerValueField(event: CGEventRef, field: u32) -> i64;
+    fn CGEventCreateKeyboardEvent(
+        source: *const c_void,
+        keycode: u16,
+        key_down: bool,
+    ) -> CGEventRef;
+    fn CGEventSetFlags(event: CGEventRef, flags: u64);
+    fn CGEventPost(tap: u32, event: CGEventRef);
 }
 
 #[link(name = "CoreFoundation", kind = "framework")]
@@ -88,12 +115,70 @@ extern "C" fn tap_callback(
         let flags = CGEventGetFlags(event);
         let keycode = CGEventGetIntegerValueField(event, CG_KEYBOARD_EVENT_KEYCODE_FIELD);
         if keycode == V_KEYCODE && (flags & CG_EVENT_FLAG_MASK_COMMAND) != 0 {
+            if ALLOW_ONCE.swap(false, Ordering::SeqCst) {
+                ONCE_PASTED.store(true, Ordering::SeqCst);
+                return event;
+            }
+            if HOLD_FOR_REVIEW.load(Ordering::SeqCst) {
+                PASTE_HELD.store(true, Ordering::SeqCst);
+            }
             return std::ptr::null_mut();
         }
     }
     event
 }
 
+/// Lets the next Cmd+V through the tap, then blocking resumes
+static ALLOW_ONCE: AtomicBool = AtomicBool::new(false);
+/// Set by the tap when it lets the allowed Cmd+V through
+static ONCE_PASTED: AtomicBool = AtomicBool::new(false);
+
+/// Allow exactly one paste while the block stays armed
+pub fn allow_once() {
+    ALLOW_ONCE.store(true, Ordering::SeqCst);
+}
+
+/// Every app in front can be detected here
+pub fn app_detection_limit() -> Option<&'static str> {
+    None
+}
+
+/// Set while a Confirm rule is armed: suppressed pastes are held for review, not dropped
+static HOLD_FOR_REVIEW: AtomicBool = AtomicBool::new(false);
+/// Set by the tap when it holds a paste; the monitor picks it up and opens the review
+static PASTE_HELD: AtomicBool = AtomicBool::new(false);
+
+/// Confirm rules hold the paste key and replay it once released
+pub const HOLDS_PASTES: bool = true;
+
+/// Bring the destination app back and replay the held Cmd+V through the tap
+pub fn release_paste(dest_app_id: &str) {
+    let dest_app_id = dest_app_id.to_string();
+    thread::spawn(move || {
+        let apps = NSRunningApplication::runningApplicationsWithBundleIdentifier(
+            &NSString::from_str(&dest_app_id),
+        );
+        if let Some(dest) = apps.firstObject() {
+            dest.activateWithOptions(NSApplicationActivationOptions::ActivateAllWindows);
+        }
+        // Give the app a moment to take focus before the keystroke lands
+        thread::sleep(Duration::from_millis(150));
+        allow_once();
+        unsafe {
+            for key_down in [true, false] {
+                let event =
+                    CGEventCreateKeyboardEvent(std::ptr::null(), V_KEYCODE as u16, key_down);
+                if event.is_null() {
+                    continue;
+                }
+                CGEventSetFlags(event, CG_EVENT_FLAG_MASK_COMMAND);
+                CGEventPost(CG_HID_EVENT_TAP, event);
+                CFRelease(event as *const c_void);
+            }
+        }
+    });
+}
+
 // --- Blocker thread ---
 
 enum BlockerMsg {
@@ -172,6 +257,9 @@ fn teardown_tap(active: &mut Option<(CFMachPortRef, CFRunLoopSourceRef)>) {
 pub struct ClipboardEvent {
     pub source_app_id: Option<String>,
     pub source_app_name: Option<String>,
+    /// Hints the source app put on the copy
+    #[serde(flatten)]
+    pub copy: rules::CopyContext,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -181,13 +269,30 @@ pub struct PasteWarning {
     pub dest_app_id: Option<String>,
     pub dest_app_name: Option<String>,
     pub blocked: bool,
+    /// Id of the rule that fired
+    pub rule_id: Option<String>,
+    pub explanation: rules::Evaluation,
 }
 
 pub struct ClipboardState {
+    pub config: Config,
+    /// Why config.json couldn't be read at startup; it's left alone until a setting changes
+    pub config_error: Option<String>,
+    pub policy: Policy,
     pub last_copy_source: Option<ClipboardEvent>,
     pub enabled: bool,
     pub rules: Vec<BlockRule>,
+    pub rule_diagnostics: Vec<RuleDiagnostic>,
+    pub rules_error: Option<String>,
+    /// Temporary allows and snoozes, including ones that have run out but not been swept yet
+    pub overrides: Vec<Override>,
     pub blocking_active: bool,
+    /// The last blocked paste, until the user picks what to do with it
+    pub pending_block: Option<crate::blocked::PendingBlock>,
+    /// Set when rules change so the monitor re-evaluates the frontmost app
+    pub recheck: bool,
+    /// A paste held by a Confirm rule while the review window is open
+    pub pending_review: Option<crate::review::PasteReview>,
 }
 
 // NOTE: These AppKit calls are made from a background thread. Apple docs say AppKit
@@ -210,6 +315,86 @@ fn get_pasteboard_change_count() -> isize {
     pb.changeCount()
 }
 
+/// Plain-text contents of the general pasteboard, if it has any
+pub fn clipboard_text() -> Option<String> {
+    let pb = NSPasteboard::generalPasteboard();
+    unsafe { pb.stringForType(NSPasteboardTypeString) }.map(|s| s.to_string())
+}
+
+/// Markers password managers put on their copies, per nspasteboard.org
+const CONCEALED_TYPE: &str = "org.nspasteboard.ConcealedType";
+const TRANSIENT_TYPE: &str = "org.nspasteboard.TransientType";
+
+/// What the copying app said about the pasteboard's content. Only the types are read.
+fn copy_context() -> rules::CopyContext {
+    let types: Vec<String> = NSPasteboard::generalPasteboard()
+        .types()
+        .iter()
+        .flat_map(|types| types.iter())
+        .map(|t| t.to_string())
+        .collect();
+    rules::CopyContext {
+        concealed: types.iter().any(|t| t == CONCEALED_TYPE),
+        transient: types.iter().any(|t| t == TRANSIENT_TYPE),
+        ..Default::default()
+    }
+}
+
+/// Every item on the pasteboard, keyed by item index and pasteboard type
+pub type Snapshot = snapshot::Snapshot<(usize, String)>;
+
+pub fn snapshot() -> Snapshot {
+    let pb = NSPasteboard::generalPasteboard();
+    let mut snapshot = Snapshot::default();
+    for (i, item) in pb
+        .pasteboardItems()
+        .iter()
+        .flat_map(|items| items.iter())
+        .enumerate()
+    {
+        for kind in item.types().iter() {
+            // Promised data that the owner won't hand over can't be put back
+            match item.dataForType(&kind) {
+                Some(data) => snapshot.push((i, kind.to_string()), data.to_vec()),
+                None => snapshot.mark_incomplete(),
+            }
+        }
+    }
+    snapshot
+}
+
+pub fn restore(snapshot: &Snapshot) {
+    let pb = NSPasteboard::generalPasteboard();
+    pb.clearContents();
+    let items: Vec<Retained<ProtocolObject<dyn NSPasteboardWriting>>> = snapshot
+        .flavors()
+        .chunk_by(|a, b| a.kind.0 == b.kind.0)
+        .map(|flavors| {
+            let item = NSPasteboardItem::new();
+            for flavor in flavors {
+                item.setData_forType(
+                    &NSData::with_bytes(&flavor.data),
+                    &NSString::from_str(&flavor.kind.1),
+                );
+            }
+            ProtocolObject::from_retained(item)
+        })
+        .collect();
+    pb.writeObjects(&NSArray::from_retained_slice(&items));
+}
+
+/// Replace the pasteboard with plain text
+pub fn write_text(text: &str) {
+    let pb = NSPasteboard::generalPasteboard();
+    pb.clearContents();
+    unsafe { pb.setString_forType(&NSString::from_str(text), NSPasteboardTypeString) };
+}
+
+/// Empty the pasteboard
+pub fn clear() {
+    NSPasteboard::generalPasteboard().clearContents();
+}
+
 fn is_cross_app(source: &ClipboardEvent, dest_bundle_id: &str) -> bool {
     match &source.source_app_id {
         Some(src_id) => !src_id.eq_ignore_ascii_case(dest_bundle_id),
@@ -228,6 +413,20 @@ pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>
         let mut last_frontmost_id: Option<String> = None;
         let mut last_warned: Option<(Option<String>, Option<String>)> = None;
         let mut block_active = false;
+        // Who the armed Confirm rule is holding pastes for
+        let mut review_context: Option<PasteReview> = None;
+        // The original clipboard while a Sanitize or Redact rule has rewritten it for the frontmost app
+        let mut rewritten: Option<Snapshot> = None;
+        // Whether `rewritten` is held back by a Block rule rather than a rewrite
+        let mut placeholder = false;
+        // Whether the held-back original is on the pasteboard for one allowed paste, and
+        // when the placeholder goes back
+        let mut serving_once = false;
+        let mut rearm_at = Instant::now();
+        // Clears the clipboard a while after a sensitive copy
+        let mut clear_timer: Option<autoclear::Timer> = None;
+        // Where recent contents were first copied from
+        let mut lineage = Lineage::default();
 
         loop {
             thread::sleep(Duration::from_millis(POLL_INTERVAL_MS));
@@ -239,6 +438,13 @@ pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>
             if current_count != last_change_count {
                 last_change_count = current_count;
                 last_warned = None;
+                // New content replaces what a Sanitize or Redact rule would have put back
+                rewritten = None;
+                placeholder = false;
+                serving_once = false;
+                ALLOW_ONCE.store(false, Ordering::SeqCst);
+                crate::blocked::withdraw(&app, &state);
+                review::resolve(&app, &state, false);
 
                 // New clipboard content — disable active block, re-evaluate on next switch
                 if block_active {
@@ -248,16 +454,41 @@ pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>
                         s.blocking_active = false;
                     }
                 }
-
+                HOLD_FOR_REVIEW.store(false, Ordering::SeqCst);
+                review_context = None;
+
+                let policy = state
+                    .lock()
+                    .ok()
+                    .map(|s| s.policy.clone())
+                    .unwrap_or_default();
+                let mut copy = copy_context();
+                copy.stamp();
+                lineage.record(
+                    &mut copy,
+                    current_id.as_deref(),
+                    current_name.as_deref(),
+                    &policy,
+                    clipboard_text,
+                );
                 let event = ClipboardEvent {
                     source_app_id: current_id.clone(),
                     source_app_name: current_name.clone(),
+                    copy,
                 };
 
                 if let Ok(mut s) = state.lock() {
                     s.last_copy_source = Some(event.clone());
                 }
 
+                // A new copy cancels any pending clear and may start its own
+                if let Some(timer) = clear_timer.take() {
+                    timer.cancel(&app);
+                }
+                clear_timer =
+                    autoclear::delay(&policy, current_id.as_deref(), &event.copy, clipboard_text)
+                        .map(|after| autoclear::Timer::start(after, current_id.clone()));
+
                 let _ = app.emit("clipboard-changed", &event);
             }
 
@@ -270,15 +501,89 @@ pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>
                         s.blocking_active = false;
                     }
                 }
+                HOLD_FOR_REVIEW.store(false, Ordering::SeqCst);
+                review_context = None;
+                if let Some(original) = rewritten.take() {
+                    restore(&original);
+                    last_change_count = get_pasteboard_change_count();
+                }
+                placeholder = false;
+                serving_once = false;
+                if let Some(timer) = clear_timer.take() {
+                    timer.cancel(&app);
+                }
                 last_frontmost_id = current_id;
                 continue;
             }
 
-            // Detect app switches
+            // Time's up and nothing was copied since: clear it
+            if clear_timer.as_mut().is_some_and(|t| t.tick(&app)) {
+                clear();
+                last_change_count = get_pasteboard_change_count();
+                // The originals held back would only bring the content back
+                rewritten = None;
+                placeholder = false;
+                serving_once = false;
+                if let Some(timer) = clear_timer.take() {
+                    timer.finish(&app);
+                }
+            }
+
+            // Allowed once while blocking by replacement: put the original back for one paste
+            if placeholder && !serving_once && ALLOW_ONCE.load(Ordering::SeqCst) {
+                if let Some(original) = &rewritten {
+                    restore(original);
+                    last_change_count = get_pasteboard_change_count();
+                }
+                serving_once = true;
+                rearm_at = Instant::now() + Duration::from_secs(ONCE_WINDOW_SECS);
+                ONCE_PASTED.store(false, Ordering::SeqCst);
+                if unsafe { AXIsProcessTrusted() } {
+                    // The tap lets exactly one Cmd+V through and holds back the rest
+                    let _ = blocker_tx.send(BlockerMsg::Enable);
+                    block_active = true;
+                } else {
+                    ALLOW_ONCE.store(false, Ordering::SeqCst);
+                }
+            }
+            if serving_once && ONCE_PASTED.swap(false, Ordering::SeqCst) {
+                rearm_at = Instant::now() + Duration::from_millis(ONCE_GRACE_MS);
+            }
+            if serving_once && Instant::now() >= rearm_at {
+                write_text(crate::blocked::PLACEHOLDER);
+                last_change_count = get_pasteboard_change_count();
+                serving_once = false;
+                if block_active {
+                    let _ = blocker_tx.send(BlockerMsg::Disable);
+                    block_active = false;
+                }
+            }
+
+            // A paste held by a Confirm rule goes to the review window
+            if PASTE_HELD.swap(false, Ordering::SeqCst) {
+                if let Some(context) = review_context.clone() {
+                    review::open(&app, &state, context.with_content(clipboard_text, &state));
+                }
+            }
+
+            // The review window has focus while open; keep the block armed for the destination
+            if state
+                .lock()
+                .ok()
+                .is_some_and(|s| s.pending_review.is_some())
+            {
+                continue;
+            }
+
+            // Detect app switches, or re-evaluate in place after a rules change
             let switched = current_id != last_frontmost_id;
             last_frontmost_id = current_id.clone();
+            let recheck = state
+                .lock()
+                .ok()
+                .is_some_and(|mut s| std::mem::take(&mut s.recheck));
 
-            if !switched {
+            if !switched && !recheck {
                 continue;
             }
 
@@ -290,6 +595,16 @@ pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>
                     s.blocking_active = false;
                 }
             }
+            HOLD_FOR_REVIEW.store(false, Ordering::SeqCst);
+            review_context = None;
+
+            // Left the app the clipboard was rewritten for — put the original back
+            if let Some(original) = rewritten.take() {
+                restore(&original);
+                last_change_count = get_pasteboard_change_count();
+            }
+            placeholder = false;
+            serving_once = false;
 
             let Some(dest_id) = &current_id else {
                 continue;
@@ -307,44 +622,84 @@ pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>
             }
 
             // Check rules
-            let current_rules = state
+            let (current_rules, current_overrides, current_policy, strategy) = state
                 .lock()
                 .ok()
-                .map(|s| s.rules.clone())
+                .map(|s| {
+                    (
+                        s.rules.clone(),
+                        s.overrides.clone(),
+                        s.policy.clone(),
+                        config::block_strategy(&s.config, &s.policy.settings),
+                    )
+                })
                 .unwrap_or_default();
-            let Some(matched) =
-                rules::matches_rule(&current_rules, source.source_app_id.as_deref(), dest_id)
-            else {
+            let evaluation = rules::evaluate_paste(
+                &current_rules,
+                &current_overrides,
+                source.source_app_id.as_deref(),
+                dest_id,
+                &source.copy,
+                None,
+                &current_policy,
+            );
+            let Some(action) = evaluation.decision.clone() else {
                 continue;
             };
 
-            // Deduplicate: skip if we already warned for this exact (src, dst) pair
+            // Deduplicate notifications for the same (src, dst) pair; blocking still re-arms
             let warn_key = (source.source_app_id.clone(), current_id.clone());
-            if last_warned.as_ref() == Some(&warn_key) {
-                continue;
-            }
+            let already_warned = last_warned.as_ref() == Some(&warn_key);
             last_warned = Some(warn_key);
 
             let src_name = source.source_app_name.as_deref().unwrap_or("Unknown app");
             let dst_name = current_name.as_deref().unwrap_or("Unknown app");
 
-            let (body, blocked) = match matched.action {
+            let (body, blocked) = match action {
                 RuleAction::Notify => (
-                    format!(
-                        "Clipboard from {}. Be careful pasting into {}.",
-                        src_name, dst_name
-                    ),
+                    match evaluation.stale_mins {
+                        Some(mins) => format!(
+                            "Clipboard from {} was copied {} minutes ago. Check it before pasting into {}.",
+                            src_name, mins, dst_name
+                        ),
+                        None => format!(
+                            "Clipboard from {}. Be careful pasting into {}.",
+                            src_name, dst_name
+                        ),
+                    },
                     false,
                 ),
                 RuleAction::Block => {
-                    let ax_trusted = unsafe { AXIsProcessTrusted() };
-                    if ax_trusted {
+                    let managed = crate::blocked::by_policy(&evaluation, &current_rules);
+                    let message = crate::blocked::message(managed, src_name, dst_name);
+                    let pending = crate::blocked::PendingBlock {
+                        source_app_id: source.source_app_id.clone(),
+                        source_app_name: source.source_app_name.clone(),
+                        dest_app_id: dest_id.clone(),
+                        dest_app_name: current_name.clone(),
+                        rule_id: evaluation.rule_id.clone(),
+                        managed,
+                    };
+                    // A clipboard too large to hold back whole falls back to the key hook
+                    let original = (strategy == BlockStrategy::ReplaceClipboard)
+                        .then(snapshot)
+                        .filter(Snapshot::is_complete);
+                    if let Some(original) = original {
+                        // Works without Accessibility, and stops menu paste too
+                        rewritten = Some(original);
+                        write_text(crate::blocked::PLACEHOLDER);
+                        last_change_count = get_pasteboard_change_count();
+                        placeholder = true;
+                        crate::blocked::offer(&app, &state, pending);
+                        (message, true)
+                    } else if unsafe { AXIsProcessTrusted() } {
                         let _ = blocker_tx.send(BlockerMsg::Enable);
                         block_active = true;
                         if let Ok(mut s) = state.lock() {
                             s.blocking_active = true;
                         }
-                        (format!("Paste blocked: {} → {}", src_name, dst_name), true)
+                        crate::blocked::offer(&app, &state, pending);
+                        (message, true)
                     } else {
                         // Fall back to notify when accessibility not granted
                         (
@@ -356,14 +711,120 @@ pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>
                         )
                     }
                 }
+                RuleAction::Confirm => {
+                    if unsafe { AXIsProcessTrusted() } {
+                        let _ = blocker_tx.send(BlockerMsg::Enable);
+                        block_active = true;
+                        HOLD_FOR_REVIEW.store(true, Ordering::SeqCst);
+                        if let Ok(mut s) = state.lock() {
+                            s.blocking_active = true;
+                        }
+                        review_context = Some(PasteReview {
+                            source_app_id: source.source_app_id.clone(),
+                            source_app_name: source.source_app_name.clone(),
+                            dest_app_id: dest_id.clone(),
+                            dest_app_name: current_name.clone(),
+                            rule_id: evaluation.rule_id.clone(),
+                            concealed: source.copy.concealed,
+                            ..Default::default()
+                        });
+                        // The review window appears when the user pastes
+                        continue;
+                    }
+                    (
+                        format!(
+                            "Clipboard from {}. Pasting into {} needs review (grant Accessibility).",
+                            src_name, dst_name
+                        ),
+                        false,
+                    )
+                }
+                // Concealed content is never read to be rewritten
+                RuleAction::Sanitize | RuleAction::Redact if source.copy.concealed => continue,
+                RuleAction::Sanitize => {
+                    let transforms = evaluation
+                        .matched
+                        .and_then(|i| current_rules.get(i))
+                        .map(|r| r.transforms.clone())
+                        .unwrap_or_default();
+                    let Some(text) = clipboard_text() else {
+                        continue;
+                    };
+                    let clean = sanitize::apply(&text, &transforms);
+                    if clean == text {
+                        continue;
+                    }
+                    // Leave alone a clipboard that couldn't be put back whole
+                    let original = snapshot();
+                    if !original.is_complete() {
+                        continue;
+                    }
+                    rewritten = Some(original);
+                    write_text(&clean);
+                    last_change_count = get_pasteboard_change_count();
+                    (
+                        format!(
+                            "Clipboard from {} was cleaned up for pasting into {}.",
+                            src_name, dst_name
+                        ),
+                        false,
+                    )
+                }
+                RuleAction::Redact => {
+                    let Some(text) = clipboard_text() else {
+                        continue;
+                    };
+                    let findings = detectors::scan_with(&text, &current_policy.detectors);
+                    let (redacted, count) = sanitize::redact(&text, &findings);
+                    if count == 0 {
+                        continue;
+                    }
+                    // Leave alone a clipboard that couldn't be put back whole
+                    let original = snapshot();
+                    if !original.is_complete() {
+                        continue;
+                    }
+                    rewritten = Some(original);
+                    write_text(&redacted);
+                    last_change_count = get_pasteboard_change_count();
+                    audit::record(
+                        &app,
+                        "paste_redacted",
+                        serde_json::json!({
+                            "source_app_id": source.source_app_id,
+                            "dest_app_id": dest_id,
+                            "rule_id": evaluation.rule_id,
+                            "redactions": count,
+                        }),
+                    );
+                    (
+                        format!(
+                            "Masked {} secret{} copied from {} for pasting into {}.",
+                            count,
+                            if count == 1 { "" } else { "s" },
+                            src_name,
+                            dst_name
+                        ),
+                        false,
+                    )
+                }
+                RuleAction::Allow => continue,
             };
 
-            let _ = app
-                .notification()
-                .builder()
-                .title("Clipboard Guard")
-                .body(body)
-                .show();
+            if already_warned {
+                continue;
+            }
+
+            if blocked {
+                crate::blocked::notify(&app, &state, &body);
+            } else {
+                let _ = app
+                    .notification()
+                    .builder()
+                    .title("Clipboard Guard")
+                    .body(body)
+                    .show();
+            }
 
             let warning = PasteWarning {
                 source_app_id: source.source_app_id,
@@ -371,6 +832,8 @@ pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>
                 dest_app_id: current_id,
                 dest_app_name: current_name,
                 blocked,
+                rule_id: evaluation.rule_id.clone(),
+                explanation: evaluation,
             };
 
             let _ = app.emit("paste-warning", &warning);
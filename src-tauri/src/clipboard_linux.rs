@@ -0,0 +1,885 @@
+use std::ffi::{c_int, c_ulong, c_void};
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+use gtk::gdk;
+use gtk::prelude::*;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+use x11_dl::xlib::{self, Xlib};
+
+use crate::config::Config;
+use crate::lineage::Lineage;
+use crate::overrides::Override;
+use crate::policy::Policy;
+use crate::rules::{self, BlockRule, RuleAction, RuleDiagnostic};
+use crate::sanitize;
+use crate::{audit, autoclear, detectors, snapshot};
+
+const POLL_INTERVAL_MS: u64 = 300;
+
+/// Set by the tray's "Allow once"; the monitor serves the original for one paste
+static ALLOW_ONCE: AtomicBool = AtomicBool::new(false);
+/// Set while the original is served for that paste, until it's been pasted or given up on
+static SERVING_ONCE: AtomicBool = AtomicBool::new(false);
+/// How long after the first request an allowed paste may keep asking for other flavors
+const ONCE_GRACE_MS: u64 = 500;
+
+/// Allow exactly one paste of the blocked content, then hold it back again
+pub fn allow_once() {
+    ALLOW_ONCE.store(true, Ordering::SeqCst);
+}
+
+/// Why rules can't see every app in front, if they can't. Only X11 windows can be
+/// detected; native Wayland windows are invisible to X11, so pastes into them go unchecked.
+pub fn app_detection_limit() -> Option<&'static str> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        Some(
+            "This is a Wayland session, which isn't supported yet. Rules, blocking \
+             included, only apply to apps running under XWayland; pastes into native \
+             Wayland apps aren't checked.",
+        )
+    } else if std::env::var_os("DISPLAY").is_none() {
+        Some("No X display was found, so rules can't tell which app is in front.")
+    } else {
+        None
+    }
+}
+
+/// There's no key hook to hold a paste with; Confirm rules only notify here
+pub const HOLDS_PASTES: bool = false;
+
+/// Never reached: nothing is held for review without `HOLDS_PASTES`
+pub fn release_paste(_dest_app_id: &str) {}
+
+// --- Types (same public API as clipboard.rs) ---
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardEvent {
+    pub source_app_id: Option<String>,
+    pub source_app_name: Option<String>,
+    /// Hints the source app put on the copy
+    #[serde(flatten)]
+    pub copy: rules::CopyContext,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasteWarning {
+    pub source_app_id: Option<String>,
+    pub source_app_name: Option<String>,
+    pub dest_app_id: Option<String>,
+    pub dest_app_name: Option<String>,
+    pub blocked: bool,
+    /// Id of the rule that fired
+    pub rule_id: Option<String>,
+    pub explanation: rules::Evaluation,
+}
+
+pub struct ClipboardState {
+    pub config: Config,
+    /// Why config.json couldn't be read at startup; it's left alone until a setting changes
+    pub config_error: Option<String>,
+    pub policy: Policy,
+    pub last_copy_source: Option<ClipboardEvent>,
+    pub enabled: bool,
+    pub rules: Vec<BlockRule>,
+    pub rule_diagnostics: Vec<RuleDiagnostic>,
+    pub rules_error: Option<String>,
+    /// Temporary allows and snoozes, including ones that have run out but not been swept yet
+    pub overrides: Vec<Override>,
+    pub blocking_active: bool,
+    /// The last blocked paste, until the user picks what to do with it
+    pub pending_block: Option<crate::blocked::PendingBlock>,
+    /// Set when rules change so the monitor re-evaluates the frontmost app
+    pub recheck: bool,
+    /// A paste held by a Confirm rule while the review window is open
+    pub pending_review: Option<crate::review::PasteReview>,
+}
+
+// --- Foreground app detection (X11 and XWayland) ---
+
+/// The monitor thread's own X connection, kept apart from GDK's
+struct X11 {
+    xlib: Xlib,
+    display: *mut xlib::Display,
+    net_active_window: xlib::Atom,
+    net_wm_pid: xlib::Atom,
+}
+
+/// The display errors are ignored for; errors on GDK's display go to its own handler
+static X11_DISPLAY: AtomicPtr<xlib::Display> = AtomicPtr::new(std::ptr::null_mut());
+static GDK_ERROR_HANDLER: OnceLock<
+    Option<unsafe extern "C" fn(*mut xlib::Display, *mut xlib::XErrorEvent) -> c_int>,
+> = OnceLock::new();
+
+/// Xlib's default handler exits the process, and the active window can close between calls
+unsafe extern "C" fn on_x_error(
+    display: *mut xlib::Display,
+    event: *mut xlib::XErrorEvent,
+) -> c_int {
+    if display == X11_DISPLAY.load(Ordering::SeqCst) {
+        return 0;
+    }
+    match GDK_ERROR_HANDLER.get().copied().flatten() {
+        Some(handler) => handler(display, event),
+        None => 0,
+    }
+}
+
+impl X11 {
+    /// None under native Wayland without XWayland
+    fn open() -> Option<Self> {
+        let xlib = Xlib::open().ok()?;
+        unsafe {
+            let display = (xlib.XOpenDisplay)(std::ptr::null());
+            if display.is_null() {
+                return None;
+            }
+            X11_DISPLAY.store(display, Ordering::SeqCst);
+            let previous = (xlib.XSetErrorHandler)(Some(on_x_error));
+            let _ = GDK_ERROR_HANDLER.set(previous);
+            let net_active_window =
+                (xlib.XInternAtom)(display, c"_NET_ACTIVE_WINDOW".as_ptr(), xlib::False);
+            let net_wm_pid = (xlib.XInternAtom)(display, c"_NET_WM_PID".as_ptr(), xlib::False);
+            Some(Self {
+                xlib,
+                display,
+                net_active_window,
+                net_wm_pid,
+            })
+        }
+    }
+
+    /// First 32-bit item of a window property
+    fn property(
+        &self,
+        window: xlib::Window,
+        property: xlib::Atom,
+        kind: xlib::Atom,
+    ) -> Option<c_ulong> {
+        let mut actual_type = 0;
+        let mut actual_format = 0;
+        let mut items = 0;
+        let mut bytes_after = 0;
+        let mut data: *mut u8 = std::ptr::null_mut();
+        unsafe {
+            let status = (self.xlib.XGetWindowProperty)(
+                self.display,
+                window,
+                property,
+                0,
+                1,
+                xlib::False,
+                kind,
+                &mut actual_type,
+                &mut actual_format,
+                &mut items,
+                &mut bytes_after,
+                &mut data,
+            );
+            if status != xlib::Success as c_int || data.is_null() {
+                return None;
+            }
+            // Xlib hands back 32-bit items as longs
+            let value = (items > 0 && actual_format == 32).then(|| *(data as *const c_ulong));
+            (self.xlib.XFree)(data as *mut c_void);
+            value
+        }
+    }
+
+    /// Returns (executable name, process name) of the active window's process.
+    /// The executable name (e.g. "firefox") is used as the app id.
+    fn frontmost_app(&self) -> (Option<String>, Option<String>) {
+        let root = unsafe { (self.xlib.XDefaultRootWindow)(self.display) };
+        let pid = self
+            .property(root, self.net_active_window, xlib::XA_WINDOW)
+            .filter(|&window| window != 0)
+            .and_then(|window| self.property(window, self.net_wm_pid, xlib::XA_CARDINAL));
+        let Some(pid) = pid else {
+            return (None, None);
+        };
+        let name = std::fs::read_to_string(format!("/proc/{}/comm", pid))
+            .ok()
+            .map(|s| s.trim().to_string());
+        // Sandboxed apps may hide their executable; fall back to the process name
+        let exe = std::fs::read_link(format!("/proc/{}/exe", pid))
+            .ok()
+            .and_then(|p| p.file_name().map(|f| f.to_string_lossy().into_owned()));
+        (exe.or_else(|| name.clone()), name)
+    }
+}
+
+impl Drop for X11 {
+    fn drop(&mut self) {
+        X11_DISPLAY.store(std::ptr::null_mut(), Ordering::SeqCst);
+        unsafe {
+            (self.xlib.XCloseDisplay)(self.display);
+        }
+    }
+}
+
+// --- Clipboard access ---
+
+/// Set once the monitor starts; GTK clipboard calls are made from the main thread
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Bumped whenever another app takes the clipboard
+static CHANGE_COUNT: AtomicU64 = AtomicU64::new(0);
+/// Ownership changes we caused ourselves, which shouldn't count as a copy
+static OWN_CHANGES: AtomicU64 = AtomicU64::new(0);
+
+/// Run `f` on the GTK main thread and wait for its result
+fn on_main<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> Option<T> {
+    if gtk::glib::MainContext::default().is_owner() {
+        return Some(f());
+    }
+    let (tx, rx) = mpsc::channel();
+    APP.get()?
+        .run_on_main_thread(move || {
+            let _ = tx.send(f());
+        })
+        .ok()?;
+    rx.recv().ok()
+}
+
+#[derive(Clone, Copy)]
+enum Selection {
+    /// Ctrl+C / Ctrl+V
+    Clipboard,
+    /// Selected text, pasted with middle-click and Shift+Insert
+    Primary,
+}
+
+impl Selection {
+    /// Note an ownership change we're about to make; only the clipboard's are watched
+    fn expect_own_change(self) {
+        if matches!(self, Selection::Clipboard) {
+            OWN_CHANGES.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn atom(self) -> gdk::Atom {
+        match self {
+            Selection::Clipboard => gdk::SELECTION_CLIPBOARD,
+            Selection::Primary => gdk::SELECTION_PRIMARY,
+        }
+    }
+
+    fn clipboard(self) -> gtk::Clipboard {
+        gtk::Clipboard::get(&self.atom())
+    }
+
+    /// Whether this process holds the selection, so giving it up is seen as a change
+    fn is_ours(self) -> bool {
+        gdk::selection_owner_get(&self.atom()).is_some()
+    }
+}
+
+fn get_change_count() -> u64 {
+    CHANGE_COUNT.load(Ordering::SeqCst)
+}
+
+/// Text on the clipboard, if it has any
+pub fn clipboard_text() -> Option<String> {
+    on_main(|| {
+        Selection::Clipboard
+            .clipboard()
+            .wait_for_text()
+            .map(|s| s.to_string())
+    })
+    .flatten()
+}
+
+/// Target KDE and password managers such as KeePassXC add to secret copies
+const PASSWORD_MANAGER_HINT: &str = "x-kde-passwordManagerHint";
+
+/// What the copying app said about the clipboard's content. Only the hint is read; there
+/// is no transient marker on Linux.
+fn copy_context() -> rules::CopyContext {
+    let concealed = on_main(|| {
+        let clipboard = Selection::Clipboard.clipboard();
+        let hint = gdk::Atom::intern(PASSWORD_MANAGER_HINT);
+        clipboard.wait_is_target_available(&hint)
+            && clipboard
+                .wait_for_contents(&hint)
+                .is_some_and(|contents| contents.data() == b"secret")
+    });
+    rules::CopyContext {
+        concealed: concealed.unwrap_or(false),
+        ..Default::default()
+    }
+}
+
+/// Targets that describe the selection rather than hold its content
+const SKIPPED_TARGETS: &[&str] = &["TARGETS", "TIMESTAMP", "MULTIPLE", "SAVE_TARGETS", "DELETE"];
+
+/// How a flavor was served, so it can be served again exactly
+#[derive(Clone)]
+pub struct Target {
+    name: String,
+    data_type: String,
+    format: i32,
+}
+
+/// Every target on the clipboard
+pub type Snapshot = snapshot::Snapshot<Target>;
+
+fn snapshot_selection(selection: Selection) -> Snapshot {
+    let taken = on_main(move || {
+        let clipboard = selection.clipboard();
+        let mut snapshot = Snapshot::default();
+        for target in clipboard.wait_for_targets().unwrap_or_default() {
+            let name = target.name().to_string();
+            if SKIPPED_TARGETS.contains(&name.as_str()) || name.starts_with("INSERT_") {
+                continue;
+            }
+            match clipboard.wait_for_contents(&target) {
+                Some(contents) => snapshot.push(
+                    Target {
+                        name,
+                        data_type: contents.data_type().name().to_string(),
+                        format: contents.format(),
+                    },
+                    contents.data(),
+                ),
+                None => snapshot.mark_incomplete(),
+            }
+        }
+        snapshot
+    });
+    taken.unwrap_or_else(|| {
+        let mut snapshot = Snapshot::default();
+        snapshot.mark_incomplete();
+        snapshot
+    })
+}
+
+/// Take ownership of the selection and serve `snapshot` from this process
+fn restore_selection(selection: Selection, snapshot: &Snapshot) {
+    serve_selection(selection, snapshot, false);
+}
+
+/// Serve the held-back original for one paste: the first app to ask gets it, and soon
+/// after (so a paste that asks for several flavors completes) the placeholder is back.
+/// Only the clipboard is served; middle-click paste stays blocked.
+fn serve_once(original: &Snapshot) {
+    SERVING_ONCE.store(true, Ordering::SeqCst);
+    serve_selection(Selection::Clipboard, original, true);
+}
+
+fn serve_selection(selection: Selection, snapshot: &Snapshot, once: bool) {
+    let snapshot = snapshot.clone();
+    on_main(move || {
+        let clipboard = selection.clipboard();
+        if snapshot.flavors().is_empty() {
+            // Clearing another app's selection does nothing and emits no owner change
+            if selection.is_ours() {
+                selection.expect_own_change();
+                clipboard.clear();
+            }
+            return;
+        }
+        selection.expect_own_change();
+        let targets: Vec<gtk::TargetEntry> = snapshot
+            .flavors()
+            .iter()
+            .enumerate()
+            .map(|(i, f)| gtk::TargetEntry::new(&f.kind.name, gtk::TargetFlags::empty(), i as u32))
+            .collect();
+        // The closure, and the data with it, is dropped when another app takes over
+        let served = clipboard.set_with_data(&targets, move |_, data, info| {
+            if let Some(f) = snapshot.flavors().get(info as usize) {
+                data.set(
+                    &gdk::Atom::intern(&f.kind.data_type),
+                    f.kind.format,
+                    &f.data,
+                );
+                if once && SERVING_ONCE.load(Ordering::SeqCst) {
+                    let grace = Duration::from_millis(ONCE_GRACE_MS);
+                    gtk::glib::timeout_add_local_once(grace, || {
+                        if SERVING_ONCE.swap(false, Ordering::SeqCst) {
+                            write_text(crate::blocked::PLACEHOLDER);
+                        }
+                    });
+                }
+            }
+        });
+        if !served && matches!(selection, Selection::Clipboard) {
+            OWN_CHANGES.fetch_sub(1, Ordering::SeqCst);
+        }
+    });
+}
+
+fn write_selection_text(selection: Selection, text: &str) {
+    let text = text.to_string();
+    on_main(move || {
+        selection.expect_own_change();
+        selection.clipboard().set_text(&text);
+    });
+}
+
+pub fn snapshot() -> Snapshot {
+    snapshot_selection(Selection::Clipboard)
+}
+
+pub fn restore(snapshot: &Snapshot) {
+    restore_selection(Selection::Clipboard, snapshot);
+}
+
+/// Replace the clipboard with plain text
+pub fn write_text(text: &str) {
+    write_selection_text(Selection::Clipboard, text);
+}
+
+/// Empty the clipboard. Another app's selection can't be dropped from here, so this takes
+/// it over with empty text.
+pub fn clear() {
+    write_text("");
+}
+
+/// Count clipboard owner changes; GTK reports them through XFixes or the Wayland data device
+fn watch_owner_changes() {
+    on_main(|| {
+        Selection::Clipboard
+            .clipboard()
+            .connect_local("owner-change", false, |_| {
+                let own = OWN_CHANGES.load(Ordering::SeqCst);
+                if own > 0 {
+                    OWN_CHANGES.store(own - 1, Ordering::SeqCst);
+                } else {
+                    CHANGE_COUNT.fetch_add(1, Ordering::SeqCst);
+                }
+                None
+            });
+    });
+}
+
+// --- Cross-app check ---
+
+fn is_cross_app(source: &ClipboardEvent, dest_app_id: &str) -> bool {
+    match &source.source_app_id {
+        Some(src_id) => !src_id.eq_ignore_ascii_case(dest_app_id),
+        None => true,
+    }
+}
+
+// --- Monitor thread + public entry point ---
+
+pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>) {
+    let _ = APP.set(app.clone());
+    watch_owner_changes();
+
+    thread::spawn(move || {
+        let x11 = X11::open();
+        if x11.is_none() {
+            eprintln!("clipboard_linux: no X display; rules can't tell which app is in front");
+        }
+        let frontmost_app = || x11.as_ref().map(X11::frontmost_app).unwrap_or((None, None));
+
+        let mut last_change_count = get_change_count();
+        let mut last_frontmost_id: Option<String> = None;
+        let mut last_warned: Option<(Option<String>, Option<String>)> = None;
+        // The original clipboard while a rule has rewritten it for the frontmost app
+        let mut rewritten: Option<Snapshot> = None;
+        // The original primary selection while a Block rule holds it back too
+        let mut primary: Option<Snapshot> = None;
+        // Whether `rewritten` is held back by a Block rule rather than a rewrite
+        let mut placeholder = false;
+        // Clears the clipboard a while after a sensitive copy
+        let mut clear_timer: Option<autoclear::Timer> = None;
+        // Where recent contents were first copied from
+        let mut lineage = Lineage::default();
+
+        // Put back whatever a rule replaced
+        let put_back = |rewritten: &mut Option<Snapshot>, primary: &mut Option<Snapshot>| {
+            SERVING_ONCE.store(false, Ordering::SeqCst);
+            if let Some(original) = rewritten.take() {
+                restore(&original);
+            }
+            if let Some(original) = primary.take() {
+                restore_selection(Selection::Primary, &original);
+            }
+        };
+
+        loop {
+            thread::sleep(Duration::from_millis(POLL_INTERVAL_MS));
+
+            let (current_id, current_name) = frontmost_app();
+
+            // Detect clipboard changes (always track, even when disabled)
+            let current_count = get_change_count();
+            if current_count != last_change_count {
+                last_change_count = current_count;
+                last_warned = None;
+                // New content replaces what a rule would have put back
+                rewritten = None;
+                placeholder = false;
+                ALLOW_ONCE.store(false, Ordering::SeqCst);
+                SERVING_ONCE.store(false, Ordering::SeqCst);
+                crate::blocked::withdraw(&app, &state);
+
+                let policy = state
+                    .lock()
+                    .ok()
+                    .map(|s| s.policy.clone())
+                    .unwrap_or_default();
+                let mut copy = copy_context();
+                copy.stamp();
+                lineage.record(
+                    &mut copy,
+                    current_id.as_deref(),
+                    current_name.as_deref(),
+                    &policy,
+                    clipboard_text,
+                );
+                let event = ClipboardEvent {
+                    source_app_id: current_id.clone(),
+                    source_app_name: current_name.clone(),
+                    copy,
+                };
+
+                if let Ok(mut s) = state.lock() {
+                    s.last_copy_source = Some(event.clone());
+                }
+
+                // A new copy cancels any pending clear and may start its own
+                if let Some(timer) = clear_timer.take() {
+                    timer.cancel(&app);
+                }
+                clear_timer =
+                    autoclear::delay(&policy, current_id.as_deref(), &event.copy, clipboard_text)
+                        .map(|after| autoclear::Timer::start(after, current_id.clone()));
+
+                let _ = app.emit("clipboard-changed", &event);
+            }
+
+            let is_enabled = state.lock().ok().map(|s| s.enabled).unwrap_or(true);
+            if !is_enabled {
+                put_back(&mut rewritten, &mut primary);
+                placeholder = false;
+                if let Some(timer) = clear_timer.take() {
+                    timer.cancel(&app);
+                }
+                last_frontmost_id = current_id;
+                continue;
+            }
+
+            // Time's up and nothing was copied since: clear it
+            if clear_timer.as_mut().is_some_and(|t| t.tick(&app)) {
+                clear();
+                last_change_count = get_change_count();
+                // The originals held back would only bring the content back
+                rewritten = None;
+                primary = None;
+                placeholder = false;
+                if let Some(timer) = clear_timer.take() {
+                    timer.finish(&app);
+                }
+            }
+
+            // Allowed once: serve the original for a single paste, then hold it back again
+            if placeholder && ALLOW_ONCE.swap(false, Ordering::SeqCst) {
+                if let Some(original) = &rewritten {
+                    serve_once(original);
+                }
+            }
+
+            // Detect app switches, or re-evaluate in place after a rules change
+            let switched = current_id != last_frontmost_id;
+            last_frontmost_id = current_id.clone();
+            let recheck = state
+                .lock()
+                .ok()
+                .is_some_and(|mut s| std::mem::take(&mut s.recheck));
+
+            if !switched && !recheck {
+                continue;
+            }
+
+            // Left the app the clipboard was replaced for — put the original back
+            put_back(&mut rewritten, &mut primary);
+            placeholder = false;
+
+            let Some(dest_id) = &current_id else {
+                continue;
+            };
+
+            let source = state.lock().ok().and_then(|s| s.last_copy_source.clone());
+
+            let Some(source) = source else {
+                continue;
+            };
+
+            if !is_cross_app(&source, dest_id) {
+                continue;
+            }
+
+            let (current_rules, current_overrides, current_policy) = state
+                .lock()
+                .ok()
+                .map(|s| (s.rules.clone(), s.overrides.clone(), s.policy.clone()))
+                .unwrap_or_default();
+            let evaluation = rules::evaluate_paste(
+                &current_rules,
+                &current_overrides,
+                source.source_app_id.as_deref(),
+                dest_id,
+                &source.copy,
+                None,
+                &current_policy,
+            );
+            let Some(action) = evaluation.decision.clone() else {
+                continue;
+            };
+
+            // Deduplicate notifications for the same (src, dst) pair; blocking still re-arms
+            let warn_key = (source.source_app_id.clone(), current_id.clone());
+            let already_warned = last_warned.as_ref() == Some(&warn_key);
+            last_warned = Some(warn_key);
+
+            let src_name = source.source_app_name.as_deref().unwrap_or("Unknown app");
+            let dst_name = current_name.as_deref().unwrap_or("Unknown app");
+
+            let (body, blocked) = match action {
+                RuleAction::Notify => (
+                    match evaluation.stale_mins {
+                        Some(mins) => format!(
+                            "Clipboard from {} was copied {} minutes ago. \
+                             Check it before pasting into {}.",
+                            src_name, mins, dst_name
+                        ),
+                        None => format!(
+                            "Clipboard from {}. Be careful pasting into {}.",
+                            src_name, dst_name
+                        ),
+                    },
+                    false,
+                ),
+                // No key hook here: Block always works by replacement
+                RuleAction::Block => {
+                    let original = snapshot();
+                    if original.is_complete() {
+                        rewritten = Some(original);
+                        write_text(crate::blocked::PLACEHOLDER);
+                        primary = Some(snapshot_selection(Selection::Primary));
+                        write_selection_text(Selection::Primary, crate::blocked::PLACEHOLDER);
+                        last_change_count = get_change_count();
+                        placeholder = true;
+                        let managed = crate::blocked::by_policy(&evaluation, &current_rules);
+                        crate::blocked::offer(
+                            &app,
+                            &state,
+                            crate::blocked::PendingBlock {
+                                source_app_id: source.source_app_id.clone(),
+                                source_app_name: source.source_app_name.clone(),
+                                dest_app_id: dest_id.clone(),
+                                dest_app_name: current_name.clone(),
+                                rule_id: evaluation.rule_id.clone(),
+                                managed,
+                            },
+                        );
+                        (crate::blocked::message(managed, src_name, dst_name), true)
+                    } else {
+                        let body = format!(
+                            "Clipboard from {} is too large to hold back from {}.",
+                            src_name, dst_name
+                        );
+                        (body, false)
+                    }
+                }
+                RuleAction::Confirm => (
+                    format!(
+                        "Clipboard from {}. Pasting into {} needs review, \
+                         which isn't supported on Linux.",
+                        src_name, dst_name
+                    ),
+                    false,
+                ),
+                // Concealed content is never read to be rewritten
+                RuleAction::Sanitize | RuleAction::Redact if source.copy.concealed => continue,
+                RuleAction::Sanitize => {
+                    let transforms = evaluation
+                        .matched
+                        .and_then(|i| current_rules.get(i))
+                        .map(|r| r.transforms.clone())
+                        .unwrap_or_default();
+                    let Some(text) = clipboard_text() else {
+                        continue;
+                    };
+                    let clean = sanitize::apply(&text, &transforms);
+                    if clean == text {
+                        continue;
+                    }
+                    // Leave alone a clipboard that couldn't be put back whole
+                    let original = snapshot();
+                    if !original.is_complete() {
+                        continue;
+                    }
+                    rewritten = Some(original);
+                    write_text(&clean);
+                    last_change_count = get_change_count();
+                    (
+                        format!(
+                            "Clipboard from {} was cleaned up for pasting into {}.",
+                            src_name, dst_name
+                        ),
+                        false,
+                    )
+                }
+                RuleAction::Redact => {
+                    let Some(text) = clipboard_text() else {
+                        continue;
+                    };
+                    let findings = detectors::scan_with(&text, &current_policy.detectors);
+                    let (redacted, count) = sanitize::redact(&text, &findings);
+                    if count == 0 {
+                        continue;
+                    }
+                    // Leave alone a clipboard that couldn't be put back whole
+                    let original = snapshot();
+                    if !original.is_complete() {
+                        continue;
+                    }
+                    rewritten = Some(original);
+                    write_text(&redacted);
+                    last_change_count = get_change_count();
+                    audit::record(
+                        &app,
+                        "paste_redacted",
+                        serde_json::json!({
+                            "source_app_id": source.source_app_id,
+                            "dest_app_id": dest_id,
+                            "rule_id": evaluation.rule_id,
+                            "redactions": count,
+                        }),
+                    );
+                    (
+                        format!(
+                            "Masked {} secret{} copied from {} for pasting into {}.",
+                            count,
+                            if count == 1 { "" } else { "s" },
+                            src_name,
+                            dst_name
+                        ),
+                        false,
+                    )
+                }
+                RuleAction::Allow => continue,
+            };
+
+            if already_warned {
+                continue;
+            }
+
+            if blocked {
+                crate::blocked::notify(&app, &state, &body);
+            } else {
+                let _ = app
+                    .notification()
+                    .builder()
+                    .title("Clipboard Guard")
+                    .body(body)
+                    .show();
+            }
+
+            let warning = PasteWarning {
+                source_app_id: source.source_app_id,
+                source_app_name: source.source_app_name,
+                dest_app_id: current_id,
+                dest_app_name: current_name,
+                blocked,
+                rule_id: evaluation.rule_id.clone(),
+                explanation: evaluation,
+            };
+
+            let _ = app.emit("paste-warning", &warning);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(name: &str, format: i32) -> Target {
+        Target {
+            name: name.to_string(),
+            data_type: name.to_string(),
+            format,
+        }
+    }
+
+    /// Set GTK up on this thread; false without a display to talk to
+    fn init_gtk() -> bool {
+        let has_display =
+            std::env::var_os("DISPLAY").is_some() || std::env::var_os("WAYLAND_DISPLAY").is_some();
+        has_display && gtk::init().is_ok()
+    }
+
+    // One test for everything that needs GTK, which can't be initialized on two threads
+    #[test]
+    fn snapshot_round_trips_every_flavor() {
+        if !init_gtk() {
+            // CI runs the tests under xvfb-run, so a missing display there is a setup bug
+            assert!(
+                std::env::var_os("CI").is_none(),
+                "no display to test GTK with"
+            );
+            eprintln!("skipping: no display");
+            return;
+        }
+        // Holding the main context makes `on_main` run calls in place
+        let context = gtk::glib::MainContext::default();
+        let _main = context.acquire().unwrap();
+        let users = snapshot();
+
+        let png: Vec<u8> = (0..=255u8).cycle().take(64 * 1024).collect();
+        let mut original = Snapshot::default();
+        original.push(
+            target("UTF8_STRING", 8),
+            "naïve → text\n".as_bytes().to_vec(),
+        );
+        original.push(target("text/html", 8), b"<b>bold</b>".to_vec());
+        original.push(target("text/rtf", 8), br"{\rtf1 plain}".to_vec());
+        original.push(target("image/png", 8), png);
+        original.push(
+            target("text/uri-list", 8),
+            b"file:///tmp/a%20b\r\n".to_vec(),
+        );
+        original.push(
+            target("application/x-clipguard-test", 8),
+            vec![0, 1, 0xff, 0],
+        );
+        restore(&original);
+
+        let taken = snapshot();
+        assert!(taken.is_complete());
+        for flavor in original.flavors() {
+            let found = taken
+                .flavors()
+                .iter()
+                .find(|f| f.kind.name == flavor.kind.name)
+                .unwrap_or_else(|| panic!("{} missing", flavor.kind.name));
+            assert_eq!(*found.data, *flavor.data, "{} changed", flavor.kind.name);
+            assert_eq!(found.kind.format, flavor.kind.format);
+        }
+
+        // A second trip serves the same bytes again
+        restore(&taken);
+        let again = snapshot();
+        assert_eq!(again.flavors().len(), taken.flavors().len());
+        for (a, b) in again.flavors().iter().zip(taken.flavors()) {
+            assert_eq!(a.kind.name, b.kind.name);
+            assert_eq!(*a.data, *b.data);
+        }
+
+        write_text("plain");
+        assert_eq!(clipboard_text().as_deref(), Some("plain"));
+
+        restore(&users);
+    }
+}
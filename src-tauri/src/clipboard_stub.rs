@@ -3,21 +3,71 @@ use std::sync::{Arc, Mutex};
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 
-use crate::rules::BlockRule;
+use crate::config::Config;
+use crate::overrides::Override;
+use crate::policy::Policy;
+use crate::rules::{self, BlockRule, RuleDiagnostic};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClipboardEvent {
     pub source_app_id: Option<String>,
     pub source_app_name: Option<String>,
+    /// Hints the source app put on the copy
+    #[serde(flatten)]
+    pub copy: rules::CopyContext,
 }
 
 pub struct ClipboardState {
+    pub config: Config,
+    /// Why config.json couldn't be read at startup; it's left alone until a setting changes
+    pub config_error: Option<String>,
+    pub policy: Policy,
     pub last_copy_source: Option<ClipboardEvent>,
     pub enabled: bool,
     pub rules: Vec<BlockRule>,
+    pub rule_diagnostics: Vec<RuleDiagnostic>,
+    pub rules_error: Option<String>,
+    /// Temporary allows and snoozes, including ones that have run out but not been swept yet
+    pub overrides: Vec<Override>,
     pub blocking_active: bool,
+    /// The last blocked paste, until the user picks what to do with it
+    pub pending_block: Option<crate::blocked::PendingBlock>,
+    /// Set when rules change so the monitor re-evaluates the frontmost app
+    pub recheck: bool,
+    pub pending_review: Option<crate::review::PasteReview>,
 }
 
+pub fn allow_once() {
+    // No paste blocking on this platform
+}
+
+/// Every app in front can be detected here
+pub fn app_detection_limit() -> Option<&'static str> {
+    None
+}
+
+pub const HOLDS_PASTES: bool = false;
+
+pub fn release_paste(_dest_app_id: &str) {
+    // No paste blocking on this platform
+}
+
+pub fn clipboard_text() -> Option<String> {
+    None
+}
+
+pub type Snapshot = crate::snapshot::Snapshot<()>;
+
+pub fn snapshot() -> Snapshot {
+    Snapshot::default()
+}
+
+pub fn restore(_snapshot: &Snapshot) {}
+
+pub fn write_text(_text: &str) {}
+
+pub fn clear() {}
+
 pub fn start_clipboard_monitor(_app: AppHandle, _state: Arc<Mutex<ClipboardState>>) {
     // Clipboard monitoring not implemented for this platform
 }
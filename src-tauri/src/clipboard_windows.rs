@@ -1,43 +1,129 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter};
 use tauri_plugin_notification::NotificationExt;
-use windows::core::PWSTR;
+use windows::core::{w, PCWSTR, PWSTR};
 use windows::Win32::Foundation::HINSTANCE;
+use windows::Win32::Foundation::HWND;
 use windows::Win32::Foundation::LPARAM;
 use windows::Win32::Foundation::WPARAM;
 use windows::Win32::Foundation::{CloseHandle, LRESULT};
-use windows::Win32::System::DataExchange::GetClipboardSequenceNumber;
+use windows::Win32::Foundation::{HANDLE, HGLOBAL};
+use windows::Win32::System::DataExchange::{
+    CloseClipboard, EmptyClipboard, EnumClipboardFormats, GetClipboardData,
+    GetClipboardSequenceNumber, IsClipboardFormatAvailable, OpenClipboard,
+    RegisterClipboardFormatW, SetClipboardData,
+};
 use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Memory::{
+    GlobalAlloc, GlobalFree, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE,
+};
 use windows::Win32::System::Threading::{
     OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
 };
-use windows::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_CONTROL};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetAsyncKeyState, SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS,
+    KEYEVENTF_KEYUP, VIRTUAL_KEY, VK_CONTROL, VK_V as VK_V_KEY,
+};
 use windows::Win32::UI::WindowsAndMessaging::{
     CallNextHookEx, DispatchMessageW, GetForegroundWindow, GetMessageW, GetWindowThreadProcessId,
-    SetWindowsHookExW, TranslateMessage, UnhookWindowsHookEx, KBDLLHOOKSTRUCT, MSG, WH_KEYBOARD_LL,
-    WM_KEYDOWN, WM_SYSKEYDOWN,
+    SetForegroundWindow, SetWindowsHookExW, TranslateMessage, UnhookWindowsHookEx, KBDLLHOOKSTRUCT,
+    MSG, WH_KEYBOARD_LL, WM_KEYDOWN, WM_SYSKEYDOWN,
 };
 
-use crate::rules::{self, BlockRule, RuleAction};
+use crate::config::{self, BlockStrategy, Config};
+use crate::lineage::Lineage;
+use crate::overrides::Override;
+use crate::policy::Policy;
+use crate::review::{self, PasteReview};
+use crate::rules::{self, BlockRule, RuleAction, RuleDiagnostic};
+use crate::sanitize;
+use crate::{audit, autoclear, detectors, snapshot};
 
 const POLL_INTERVAL_MS: u64 = 300;
+/// How long an allowed paste may keep reading the original before the placeholder is back
+const ONCE_GRACE_MS: u64 = 500;
+/// The longest an allowed paste is served when the paste itself can't be seen (menu paste,
+/// Shift+Insert)
+const ONCE_WINDOW_SECS: u64 = 10;
 const VK_V: u32 = 0x56;
+const CF_UNICODETEXT: u32 = 13;
 
 /// Global flag read by the keyboard hook callback to decide whether to suppress Ctrl+V.
 static BLOCK_PASTE: AtomicBool = AtomicBool::new(false);
 
+/// Lets the next Ctrl+V through the hook, then blocking resumes
+static ALLOW_ONCE: AtomicBool = AtomicBool::new(false);
+/// Set by the hook when it lets the allowed Ctrl+V through
+static ONCE_PASTED: AtomicBool = AtomicBool::new(false);
+
+/// Allow exactly one paste while the block stays armed
+pub fn allow_once() {
+    ALLOW_ONCE.store(true, Ordering::SeqCst);
+}
+
+/// Every app in front can be detected here
+pub fn app_detection_limit() -> Option<&'static str> {
+    None
+}
+
+/// Set while a Confirm rule is armed: suppressed pastes are held for review, not dropped
+static HOLD_FOR_REVIEW: AtomicBool = AtomicBool::new(false);
+/// Set by the hook when it holds a paste; the monitor picks it up and opens the review
+static PASTE_HELD: AtomicBool = AtomicBool::new(false);
+/// Foreground window the held paste was aimed at
+static HELD_WINDOW: AtomicPtr<c_void> = AtomicPtr::new(std::ptr::null_mut());
+
+/// Confirm rules hold the paste key and replay it once released
+pub const HOLDS_PASTES: bool = true;
+
+/// Bring the destination window back and replay the held Ctrl+V through the hook
+pub fn release_paste(_dest_app_id: &str) {
+    let hwnd = HWND(HELD_WINDOW.load(Ordering::SeqCst));
+    thread::spawn(move || unsafe {
+        if !hwnd.0.is_null() {
+            let _ = SetForegroundWindow(hwnd);
+        }
+        // Give the window a moment to take focus before the keystroke lands
+        thread::sleep(Duration::from_millis(150));
+        allow_once();
+        let key = |vk: VIRTUAL_KEY, flags: KEYBD_EVENT_FLAGS| INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: KEYBDINPUT {
+                    wVk: vk,
+                    wScan: 0,
+                    dwFlags: flags,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        };
+        let inputs = [
+            key(VK_CONTROL, KEYBD_EVENT_FLAGS(0)),
+            key(VK_V_KEY, KEYBD_EVENT_FLAGS(0)),
+            key(VK_V_KEY, KEYEVENTF_KEYUP),
+            key(VK_CONTROL, KEYEVENTF_KEYUP),
+        ];
+        SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+    });
+}
+
 // --- Types (same public API as clipboard.rs) ---
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClipboardEvent {
     pub source_app_id: Option<String>,
     pub source_app_name: Option<String>,
+    /// Hints the source app put on the copy
+    #[serde(flatten)]
+    pub copy: rules::CopyContext,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,13 +133,30 @@ pub struct PasteWarning {
     pub dest_app_id: Option<String>,
     pub dest_app_name: Option<String>,
     pub blocked: bool,
+    /// Id of the rule that fired
+    pub rule_id: Option<String>,
+    pub explanation: rules::Evaluation,
 }
 
 pub struct ClipboardState {
+    pub config: Config,
+    /// Why config.json couldn't be read at startup; it's left alone until a setting changes
+    pub config_error: Option<String>,
+    pub policy: Policy,
     pub last_copy_source: Option<ClipboardEvent>,
     pub enabled: bool,
     pub rules: Vec<BlockRule>,
+    pub rule_diagnostics: Vec<RuleDiagnostic>,
+    pub rules_error: Option<String>,
+    /// Temporary allows and snoozes, including ones that have run out but not been swept yet
+    pub overrides: Vec<Override>,
     pub blocking_active: bool,
+    /// The last blocked paste, until the user picks what to do with it
+    pub pending_block: Option<crate::blocked::PendingBlock>,
+    /// Set when rules change so the monitor re-evaluates the frontmost app
+    pub recheck: bool,
+    /// A paste held by a Confirm rule while the review window is open
+    pub pending_review: Option<crate::review::PasteReview>,
 }
 
 // --- Foreground app detection ---
@@ -110,6 +213,166 @@ fn get_clipboard_sequence() -> u32 {
     unsafe { GetClipboardSequenceNumber() }
 }
 
+/// Unicode text on the clipboard, if it has any
+pub fn clipboard_text() -> Option<String> {
+    unsafe {
+        OpenClipboard(None).ok()?;
+        let text = GetClipboardData(CF_UNICODETEXT).ok().and_then(|handle| {
+            let global = HGLOBAL(handle.0);
+            // Stop at the block's end if the owner left out the terminating NUL
+            let units = GlobalSize(global) / 2;
+            let ptr = GlobalLock(global) as *const u16;
+            if ptr.is_null() {
+                return None;
+            }
+            let data = std::slice::from_raw_parts(ptr, units);
+            let len = data.iter().position(|&c| c == 0).unwrap_or(units);
+            let text = String::from_utf16_lossy(&data[..len]);
+            let _ = GlobalUnlock(global);
+            Some(text)
+        });
+        let _ = CloseClipboard();
+        text
+    }
+}
+
+/// Formats password managers register and add to their copies, per the clipboard docs
+const EXCLUDE_FROM_MONITORING: PCWSTR = w!("ExcludeClipboardContentFromMonitorProcessing");
+const CAN_INCLUDE_IN_HISTORY: PCWSTR = w!("CanIncludeInClipboardHistory");
+
+/// What the copying app said about the clipboard's content. Only the marker formats are
+/// read.
+fn copy_context() -> rules::CopyContext {
+    let mut copy = rules::CopyContext::default();
+    if !open_clipboard() {
+        return copy;
+    }
+    unsafe {
+        let exclude = RegisterClipboardFormatW(EXCLUDE_FROM_MONITORING);
+        copy.concealed = exclude != 0 && IsClipboardFormatAvailable(exclude).is_ok();
+        // A DWORD of 0 keeps the copy out of clipboard history
+        let history = RegisterClipboardFormatW(CAN_INCLUDE_IN_HISTORY);
+        copy.transient = history != 0
+            && GetClipboardData(history)
+                .ok()
+                .and_then(|h| read_global(HGLOBAL(h.0)))
+                .is_some_and(|data| data.starts_with(&0u32.to_le_bytes()));
+        let _ = CloseClipboard();
+    }
+    copy
+}
+
+/// GDI handles rather than memory blocks; Windows synthesizes them from CF_DIB anyway
+const SKIPPED_FORMATS: &[u32] = &[
+    2,  // CF_BITMAP
+    3,  // CF_METAFILEPICT
+    9,  // CF_PALETTE
+    14, // CF_ENHMETAFILE
+];
+
+/// Every format on the clipboard, keyed by format number
+pub type Snapshot = snapshot::Snapshot<u32>;
+
+/// Another app may hold the clipboard open for a moment; retry briefly
+fn open_clipboard() -> bool {
+    for _ in 0..10 {
+        if unsafe { OpenClipboard(None) }.is_ok() {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
+
+unsafe fn read_global(global: HGLOBAL) -> Option<Vec<u8>> {
+    let size = GlobalSize(global);
+    let ptr = GlobalLock(global) as *const u8;
+    if ptr.is_null() {
+        return None;
+    }
+    let data = std::slice::from_raw_parts(ptr, size).to_vec();
+    let _ = GlobalUnlock(global);
+    Some(data)
+}
+
+/// Hand a copy of `data` to the clipboard; it owns the memory on success
+unsafe fn set_global(format: u32, data: &[u8]) {
+    let Ok(global) = GlobalAlloc(GMEM_MOVEABLE, data.len()) else {
+        return;
+    };
+    let ptr = GlobalLock(global) as *mut u8;
+    if ptr.is_null() {
+        let _ = GlobalFree(Some(global));
+        return;
+    }
+    std::ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len());
+    let _ = GlobalUnlock(global);
+    if SetClipboardData(format, Some(HANDLE(global.0))).is_err() {
+        let _ = GlobalFree(Some(global));
+    }
+}
+
+pub fn snapshot() -> Snapshot {
+    let mut snapshot = Snapshot::default();
+    if !open_clipboard() {
+        snapshot.mark_incomplete();
+        return snapshot;
+    }
+    unsafe {
+        let mut format = EnumClipboardFormats(0);
+        while format != 0 {
+            if !SKIPPED_FORMATS.contains(&format) {
+                match GetClipboardData(format)
+                    .ok()
+                    .and_then(|h| read_global(HGLOBAL(h.0)))
+                {
+                    Some(data) => snapshot.push(format, data),
+                    None => snapshot.mark_incomplete(),
+                }
+            }
+            format = EnumClipboardFormats(format);
+        }
+        let _ = CloseClipboard();
+    }
+    snapshot
+}
+
+pub fn restore(snapshot: &Snapshot) {
+    if !open_clipboard() {
+        return;
+    }
+    unsafe {
+        let _ = EmptyClipboard();
+        for flavor in snapshot.flavors() {
+            set_global(flavor.kind, &flavor.data);
+        }
+        let _ = CloseClipboard();
+    }
+}
+
+/// Replace the clipboard with plain text
+pub fn write_text(text: &str) {
+    let bytes: Vec<u8> = text
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    let mut snapshot = Snapshot::default();
+    snapshot.push(CF_UNICODETEXT, bytes);
+    restore(&snapshot);
+}
+
+/// Empty the clipboard
+pub fn clear() {
+    if !open_clipboard() {
+        return;
+    }
+    unsafe {
+        let _ = EmptyClipboard();
+        let _ = CloseClipboard();
+    }
+}
+
 // --- Cross-app check ---
 
 fn is_cross_app(source: &ClipboardEvent, dest_app_id: &str) -> bool {
@@ -127,7 +390,13 @@ unsafe extern "system" fn keyboard_hook_proc(code: i32, wparam: WPARAM, lparam:
         let is_keydown = wparam.0 == WM_KEYDOWN as usize || wparam.0 == WM_SYSKEYDOWN as usize;
         if is_keydown && info.vkCode == VK_V {
             let ctrl = GetAsyncKeyState(VK_CONTROL.0 as i32);
-            if ctrl < 0 {
+            if ctrl < 0 && ALLOW_ONCE.swap(false, Ordering::SeqCst) {
+                ONCE_PASTED.store(true, Ordering::SeqCst);
+            } else if ctrl < 0 {
+                if HOLD_FOR_REVIEW.load(Ordering::SeqCst) {
+                    HELD_WINDOW.store(GetForegroundWindow().0, Ordering::SeqCst);
+                    PASTE_HELD.store(true, Ordering::SeqCst);
+                }
                 // Suppress the keystroke
                 return LRESULT(1);
             }
@@ -195,6 +464,20 @@ pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>
         let mut last_frontmost_id: Option<String> = None;
         let mut last_warned: Option<(Option<String>, Option<String>)> = None;
         let mut block_active = false;
+        // Who the armed Confirm rule is holding pastes for
+        let mut review_context: Option<PasteReview> = None;
+        // The original clipboard while a Sanitize or Redact rule has rewritten it for the frontmost app
+        let mut rewritten: Option<Snapshot> = None;
+        // Whether `rewritten` is held back by a Block rule rather than a rewrite
+        let mut placeholder = false;
+        // Whether the held-back original is on the clipboard for one allowed paste, and
+        // when the placeholder goes back
+        let mut serving_once = false;
+        let mut rearm_at = Instant::now();
+        // Clears the clipboard a while after a sensitive copy
+        let mut clear_timer: Option<autoclear::Timer> = None;
+        // Where recent contents were first copied from
+        let mut lineage = Lineage::default();
 
         loop {
             thread::sleep(Duration::from_millis(POLL_INTERVAL_MS));
@@ -206,6 +489,13 @@ pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>
             if current_seq != last_seq {
                 last_seq = current_seq;
                 last_warned = None;
+                // New content replaces what a Sanitize or Redact rule would have put back
+                rewritten = None;
+                placeholder = false;
+                serving_once = false;
+                ALLOW_ONCE.store(false, Ordering::SeqCst);
+                crate::blocked::withdraw(&app, &state);
+                review::resolve(&app, &state, false);
 
                 if block_active {
                     let _ = blocker_tx.send(BlockerMsg::Disable);
@@ -214,16 +504,41 @@ pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>
                         s.blocking_active = false;
                     }
                 }
-
+                HOLD_FOR_REVIEW.store(false, Ordering::SeqCst);
+                review_context = None;
+
+                let policy = state
+                    .lock()
+                    .ok()
+                    .map(|s| s.policy.clone())
+                    .unwrap_or_default();
+                let mut copy = copy_context();
+                copy.stamp();
+                lineage.record(
+                    &mut copy,
+                    current_id.as_deref(),
+                    current_name.as_deref(),
+                    &policy,
+                    clipboard_text,
+                );
                 let event = ClipboardEvent {
                     source_app_id: current_id.clone(),
                     source_app_name: current_name.clone(),
+                    copy,
                 };
 
                 if let Ok(mut s) = state.lock() {
                     s.last_copy_source = Some(event.clone());
                 }
 
+                // A new copy cancels any pending clear and may start its own
+                if let Some(timer) = clear_timer.take() {
+                    timer.cancel(&app);
+                }
+                clear_timer =
+                    autoclear::delay(&policy, current_id.as_deref(), &event.copy, clipboard_text)
+                        .map(|after| autoclear::Timer::start(after, current_id.clone()));
+
                 let _ = app.emit("clipboard-changed", &event);
             }
 
@@ -236,15 +551,85 @@ pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>
                         s.blocking_active = false;
                     }
                 }
+                HOLD_FOR_REVIEW.store(false, Ordering::SeqCst);
+                review_context = None;
+                if let Some(original) = rewritten.take() {
+                    restore(&original);
+                    last_seq = get_clipboard_sequence();
+                }
+                placeholder = false;
+                serving_once = false;
+                if let Some(timer) = clear_timer.take() {
+                    timer.cancel(&app);
+                }
                 last_frontmost_id = current_id;
                 continue;
             }
 
-            // Detect app switches
+            // Time's up and nothing was copied since: clear it
+            if clear_timer.as_mut().is_some_and(|t| t.tick(&app)) {
+                clear();
+                last_seq = get_clipboard_sequence();
+                // The originals held back would only bring the content back
+                rewritten = None;
+                placeholder = false;
+                serving_once = false;
+                if let Some(timer) = clear_timer.take() {
+                    timer.finish(&app);
+                }
+            }
+
+            // Allowed once while blocking by replacement: put the original back for one paste
+            if placeholder && !serving_once && ALLOW_ONCE.load(Ordering::SeqCst) {
+                if let Some(original) = &rewritten {
+                    restore(original);
+                    last_seq = get_clipboard_sequence();
+                }
+                serving_once = true;
+                rearm_at = Instant::now() + Duration::from_secs(ONCE_WINDOW_SECS);
+                ONCE_PASTED.store(false, Ordering::SeqCst);
+                // The hook lets exactly one Ctrl+V through and holds back the rest
+                let _ = blocker_tx.send(BlockerMsg::Enable);
+                block_active = true;
+            }
+            if serving_once && ONCE_PASTED.swap(false, Ordering::SeqCst) {
+                rearm_at = Instant::now() + Duration::from_millis(ONCE_GRACE_MS);
+            }
+            if serving_once && Instant::now() >= rearm_at {
+                write_text(crate::blocked::PLACEHOLDER);
+                last_seq = get_clipboard_sequence();
+                serving_once = false;
+                if block_active {
+                    let _ = blocker_tx.send(BlockerMsg::Disable);
+                    block_active = false;
+                }
+            }
+
+            // A paste held by a Confirm rule goes to the review window
+            if PASTE_HELD.swap(false, Ordering::SeqCst) {
+                if let Some(context) = review_context.clone() {
+                    review::open(&app, &state, context.with_content(clipboard_text, &state));
+                }
+            }
+
+            // The review window has focus while open; keep the block armed for the destination
+            if state
+                .lock()
+                .ok()
+                .is_some_and(|s| s.pending_review.is_some())
+            {
+                continue;
+            }
+
+            // Detect app switches, or re-evaluate in place after a rules change
             let switched = current_id != last_frontmost_id;
             last_frontmost_id = current_id.clone();
+            let recheck = state
+                .lock()
+                .ok()
+                .is_some_and(|mut s| std::mem::take(&mut s.recheck));
 
-            if !switched {
+            if !switched && !recheck {
                 continue;
             }
 
@@ -256,6 +641,16 @@ pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>
                     s.blocking_active = false;
                 }
             }
+            HOLD_FOR_REVIEW.store(false, Ordering::SeqCst);
+            review_context = None;
+
+            // Left the app the clipboard was rewritten for — put the original back
+            if let Some(original) = rewritten.take() {
+                restore(&original);
+                last_seq = get_clipboard_sequence();
+            }
+            placeholder = false;
+            serving_once = false;
 
             let Some(dest_id) = &current_id else {
                 continue;
@@ -271,50 +666,191 @@ pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>
                 continue;
             }
 
-            let current_rules = state
+            let (current_rules, current_overrides, current_policy, strategy) = state
                 .lock()
                 .ok()
-                .map(|s| s.rules.clone())
+                .map(|s| {
+                    (
+                        s.rules.clone(),
+                        s.overrides.clone(),
+                        s.policy.clone(),
+                        config::block_strategy(&s.config, &s.policy.settings),
+                    )
+                })
                 .unwrap_or_default();
-            let Some(matched) =
-                rules::matches_rule(&current_rules, source.source_app_id.as_deref(), dest_id)
-            else {
+            let evaluation = rules::evaluate_paste(
+                &current_rules,
+                &current_overrides,
+                source.source_app_id.as_deref(),
+                dest_id,
+                &source.copy,
+                None,
+                &current_policy,
+            );
+            let Some(action) = evaluation.decision.clone() else {
                 continue;
             };
 
+            // Deduplicate notifications for the same (src, dst) pair; blocking still re-arms
             let warn_key = (source.source_app_id.clone(), current_id.clone());
-            if last_warned.as_ref() == Some(&warn_key) {
-                continue;
-            }
+            let already_warned = last_warned.as_ref() == Some(&warn_key);
             last_warned = Some(warn_key);
 
             let src_name = source.source_app_name.as_deref().unwrap_or("Unknown app");
             let dst_name = current_name.as_deref().unwrap_or("Unknown app");
 
-            let (body, blocked) = match matched.action {
+            let (body, blocked) = match action {
                 RuleAction::Notify => (
-                    format!(
-                        "Clipboard from {}. Be careful pasting into {}.",
-                        src_name, dst_name
-                    ),
+                    match evaluation.stale_mins {
+                        Some(mins) => format!(
+                            "Clipboard from {} was copied {} minutes ago. Check it before pasting into {}.",
+                            src_name, mins, dst_name
+                        ),
+                        None => format!(
+                            "Clipboard from {}. Be careful pasting into {}.",
+                            src_name, dst_name
+                        ),
+                    },
                     false,
                 ),
                 RuleAction::Block => {
+                    // A clipboard too large to hold back whole falls back to the key hook
+                    let original = (strategy == BlockStrategy::ReplaceClipboard)
+                        .then(snapshot)
+                        .filter(Snapshot::is_complete);
+                    if let Some(original) = original {
+                        // Stops context-menu paste and Shift+Insert as well as Ctrl+V
+                        rewritten = Some(original);
+                        write_text(crate::blocked::PLACEHOLDER);
+                        last_seq = get_clipboard_sequence();
+                        placeholder = true;
+                    } else {
+                        let _ = blocker_tx.send(BlockerMsg::Enable);
+                        block_active = true;
+                        if let Ok(mut s) = state.lock() {
+                            s.blocking_active = true;
+                        }
+                    }
+                    let managed = crate::blocked::by_policy(&evaluation, &current_rules);
+                    crate::blocked::offer(
+                        &app,
+                        &state,
+                        crate::blocked::PendingBlock {
+                            source_app_id: source.source_app_id.clone(),
+                            source_app_name: source.source_app_name.clone(),
+                            dest_app_id: dest_id.clone(),
+                            dest_app_name: current_name.clone(),
+                            rule_id: evaluation.rule_id.clone(),
+                            managed,
+                        },
+                    );
+                    (crate::blocked::message(managed, src_name, dst_name), true)
+                }
+                RuleAction::Confirm => {
                     let _ = blocker_tx.send(BlockerMsg::Enable);
                     block_active = true;
+                    HOLD_FOR_REVIEW.store(true, Ordering::SeqCst);
                     if let Ok(mut s) = state.lock() {
                         s.blocking_active = true;
                     }
-                    (format!("Paste blocked: {} → {}", src_name, dst_name), true)
+                    review_context = Some(PasteReview {
+                        source_app_id: source.source_app_id.clone(),
+                        source_app_name: source.source_app_name.clone(),
+                        dest_app_id: dest_id.clone(),
+                        dest_app_name: current_name.clone(),
+                        rule_id: evaluation.rule_id.clone(),
+                        concealed: source.copy.concealed,
+                        ..Default::default()
+                    });
+                    // The review window appears when the user pastes
+                    continue;
+                }
+                // Concealed content is never read to be rewritten
+                RuleAction::Sanitize | RuleAction::Redact if source.copy.concealed => continue,
+                RuleAction::Sanitize => {
+                    let transforms = evaluation
+                        .matched
+                        .and_then(|i| current_rules.get(i))
+                        .map(|r| r.transforms.clone())
+                        .unwrap_or_default();
+                    let Some(text) = clipboard_text() else {
+                        continue;
+                    };
+                    let clean = sanitize::apply(&text, &transforms);
+                    if clean == text {
+                        continue;
+                    }
+                    // Leave alone a clipboard that couldn't be put back whole
+                    let original = snapshot();
+                    if !original.is_complete() {
+                        continue;
+                    }
+                    rewritten = Some(original);
+                    write_text(&clean);
+                    last_seq = get_clipboard_sequence();
+                    (
+                        format!(
+                            "Clipboard from {} was cleaned up for pasting into {}.",
+                            src_name, dst_name
+                        ),
+                        false,
+                    )
+                }
+                RuleAction::Redact => {
+                    let Some(text) = clipboard_text() else {
+                        continue;
+                    };
+                    let findings = detectors::scan_with(&text, &current_policy.detectors);
+                    let (redacted, count) = sanitize::redact(&text, &findings);
+                    if count == 0 {
+                        continue;
+                    }
+                    // Leave alone a clipboard that couldn't be put back whole
+                    let original = snapshot();
+                    if !original.is_complete() {
+                        continue;
+                    }
+                    rewritten = Some(original);
+                    write_text(&redacted);
+                    last_seq = get_clipboard_sequence();
+                    audit::record(
+                        &app,
+                        "paste_redacted",
+                        serde_json::json!({
+                            "source_app_id": source.source_app_id,
+                            "dest_app_id": dest_id,
+                            "rule_id": evaluation.rule_id,
+                            "redactions": count,
+                        }),
+                    );
+                    (
+                        format!(
+                            "Masked {} secret{} copied from {} for pasting into {}.",
+                            count,
+                            if count == 1 { "" } else { "s" },
+                            src_name,
+                            dst_name
+                        ),
+                        false,
+                    )
                 }
+                RuleAction::Allow => continue,
             };
 
-            let _ = app
-                .notification()
-                .builder()
-                .title("Clipboard Guard")
-                .body(body)
-                .show();
+            if already_warned {
+                continue;
+            }
+
+            if blocked {
+                crate::blocked::notify(&app, &state, &body);
+            } else {
+                let _ = app
+                    .notification()
+                    .builder()
+                    .title("Clipboard Guard")
+                    .body(body)
+                    .show();
+            }
 
             let warning = PasteWarning {
                 source_app_id: source.source_app_id,
@@ -322,6 +858,8 @@ pub fn start_clipboard_monitor(app: AppHandle, state: Arc<Mutex<ClipboardState>>
                 dest_app_id: current_id,
                 dest_app_name: current_name,
                 blocked,
+                rule_id: evaluation.rule_id.clone(),
+                explanation: evaluation,
             };
 
             let _ = app.emit("paste-warning", &warning);
@@ -1,46 +1,69 @@
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
-use tauri::Manager;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::policy::PolicySettings;
+use crate::storage::{self, Loaded};
+
+const CONFIG_FILE: &str = "config.json";
+
+/// How Block rules stop a paste
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockStrategy {
+    /// Swallow Cmd/Ctrl+V with a key hook; needs Accessibility on macOS
+    #[default]
+    KeyHook,
+    /// Swap the clipboard for a placeholder while the blocked app is in front, which
+    /// also stops menu paste, middle-click and Shift+Insert
+    ReplaceClipboard,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Config {
     #[serde(default)]
     pub autostart_initialized: bool,
+    #[serde(default)]
+    pub block_strategy: BlockStrategy,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             autostart_initialized: false,
+            block_strategy: BlockStrategy::default(),
         }
     }
 }
 
-fn config_path(app: &tauri::AppHandle) -> Option<PathBuf> {
-    app.path()
-        .app_data_dir()
-        .ok()
-        .map(|d| d.join("config.json"))
+/// The strategy in force: the policy's if it pins one, else the user's
+pub fn block_strategy(config: &Config, settings: &PolicySettings) -> BlockStrategy {
+    settings.block_strategy.unwrap_or(config.block_strategy)
 }
 
-pub fn load(app: &tauri::AppHandle) -> Config {
-    let Some(path) = config_path(app) else {
-        return Config::default();
+/// Load the config. A corrupt file is replaced by its newest valid backup; if there is
+/// none the file is left alone so the caller can report the error.
+pub fn load(app: &tauri::AppHandle) -> Result<Loaded<Config>, String> {
+    let default = || Loaded {
+        value: Config::default(),
+        recovery: None,
     };
-    match fs::read_to_string(&path) {
-        Ok(json) => serde_json::from_str(&json).unwrap_or_default(),
-        Err(_) => Config::default(),
-    }
+    let Some(path) = storage::data_path(app, CONFIG_FILE) else {
+        return Ok(default());
+    };
+    let parse = |json: &str| serde_json::from_str(json).map_err(|e| e.to_string());
+    Ok(storage::read_with_recovery(&path, parse)?.unwrap_or_else(default))
+}
+
+/// Read config.json as it is on disk, without falling back to backups or defaults
+pub fn read(app: &tauri::AppHandle) -> Result<Config, String> {
+    let path = storage::data_path(app, CONFIG_FILE).ok_or("no app data dir")?;
+    let json = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&json).map_err(|e| format!("{}: {}", path.display(), e))
 }
 
 pub fn save(app: &tauri::AppHandle, config: &Config) -> Result<(), String> {
-    let Some(path) = config_path(app) else {
+    let Some(path) = storage::data_path(app, CONFIG_FILE) else {
         return Err("no app data dir".into());
     };
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
     let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
-    fs::write(&path, json).map_err(|e| e.to_string())
+    storage::write_atomic(&path, json.as_bytes())
 }
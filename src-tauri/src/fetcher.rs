@@ -0,0 +1,323 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter};
+
+use crate::bundle::{self, BundlePayload, SignedBundle};
+use crate::clipboard::ClipboardState;
+use crate::policy::{self, Policy};
+use crate::storage;
+use crate::watcher::RulesReloaded;
+
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+const MAX_BACKOFF_SECS: u64 = 60 * 60;
+/// Bundles are small JSON documents; anything bigger is not one
+const MAX_BUNDLE_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug)]
+pub enum FetchError {
+    /// Couldn't reach the server or it returned an error — retry later
+    Network(String),
+    /// The server answered with something we refuse to apply
+    Rejected(String),
+}
+
+#[derive(Debug)]
+pub enum FetchOutcome {
+    NotModified,
+    Updated(Box<BundlePayload>),
+}
+
+/// Polls one URL for signed bundles, remembering the ETag between requests
+pub struct Fetcher {
+    url: String,
+    cache_path: PathBuf,
+    trusted_keys: Vec<String>,
+    etag: Option<String>,
+    agent: ureq::Agent,
+}
+
+impl Fetcher {
+    pub fn new(url: &str, cache_path: PathBuf, trusted_keys: Vec<String>) -> Result<Self, String> {
+        check_url(url)?;
+        let agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .timeout_global(Some(Duration::from_secs(REQUEST_TIMEOUT_SECS)))
+            .build()
+            .into();
+        Ok(Self {
+            url: url.to_string(),
+            cache_path,
+            trusted_keys,
+            etag: None,
+            agent,
+        })
+    }
+
+    /// One conditional GET. A new bundle is verified and cached before it is returned.
+    pub fn poll(&mut self) -> Result<FetchOutcome, FetchError> {
+        let mut request = self.agent.get(&self.url);
+        if let Some(etag) = &self.etag {
+            request = request.header("If-None-Match", etag);
+        }
+        let mut response = request
+            .call()
+            .map_err(|e| FetchError::Network(e.to_string()))?;
+
+        match response.status().as_u16() {
+            304 => return Ok(FetchOutcome::NotModified),
+            200 => {}
+            status => return Err(FetchError::Network(format!("HTTP {}", status))),
+        }
+
+        let etag = response
+            .headers()
+            .get("etag")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response
+            .body_mut()
+            .with_config()
+            .limit(MAX_BUNDLE_BYTES)
+            .read_to_string()
+            .map_err(|e| FetchError::Network(e.to_string()))?;
+        let candidate: SignedBundle = serde_json::from_str(&body)
+            .map_err(|e| FetchError::Rejected(format!("not a policy bundle: {}", e)))?;
+        let payload = bundle::accept(&candidate, &self.cache_path, &self.trusted_keys)
+            .map_err(FetchError::Rejected)?;
+
+        // Only remember the ETag once the bundle was accepted, so a bad one is re-checked
+        self.etag = etag;
+        Ok(FetchOutcome::Updated(Box::new(payload)))
+    }
+}
+
+/// Policy must come over HTTPS; plain HTTP is allowed for loopback only (local testing)
+fn check_url(url: &str) -> Result<(), String> {
+    let lower = url.to_ascii_lowercase();
+    let loopback = ["http://127.0.0.1", "http://localhost", "http://[::1]"];
+    let is_loopback = loopback.iter().any(|prefix| {
+        lower
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with([':', '/']))
+    });
+    if lower.starts_with("https://") || is_loopback {
+        Ok(())
+    } else {
+        Err(format!("policy URL must use https: {}", url))
+    }
+}
+
+/// Start polling when the managed policy configures a remote and pins signing keys
+pub fn start(app: AppHandle, state: Arc<Mutex<ClipboardState>>) {
+    let Some(current) = state.lock().ok().map(|s| s.policy.clone()) else {
+        return;
+    };
+    let Some(remote) = current.remote.clone() else {
+        return;
+    };
+    if current.trusted_keys.is_empty() {
+        eprintln!("fetcher: remote policy needs trusted_keys; not polling");
+        return;
+    }
+    let Some(cache_path) = storage::data_path(&app, bundle::CACHE_FILE) else {
+        return;
+    };
+    let mut fetcher = match Fetcher::new(&remote.url, cache_path, current.trusted_keys.clone()) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("fetcher: {}", e);
+            return;
+        }
+    };
+    // Bundles are layered over policy.json, not over whatever bundle is in force now
+    let last_good = storage::data_path(&app, policy::LAST_GOOD_FILE);
+    let (base, _) = policy::load(last_good.as_deref());
+    let interval = Duration::from_secs(remote.interval_secs.max(1));
+
+    thread::spawn(move || {
+        let mut delay = interval;
+        loop {
+            match fetcher.poll() {
+                Ok(FetchOutcome::Updated(payload)) => {
+                    delay = interval;
+                    apply(&app, &state, bundle::apply(&base, &payload));
+                }
+                Ok(FetchOutcome::NotModified) => delay = interval,
+                Err(FetchError::Network(e)) => {
+                    eprintln!("fetcher: {}", e);
+                    delay = (delay * 2).min(Duration::from_secs(MAX_BACKOFF_SECS));
+                }
+                Err(FetchError::Rejected(e)) => {
+                    crate::report_policy_alert(&app, &e);
+                    delay = (delay * 2).min(Duration::from_secs(MAX_BACKOFF_SECS));
+                }
+            }
+            thread::sleep(delay);
+        }
+    });
+}
+
+/// Install an updated policy through the same path as `set_rules`
+fn apply(app: &AppHandle, state: &Arc<Mutex<ClipboardState>>, new_policy: Policy) {
+    let committed = crate::commit_rules(app, state, |s| {
+        Ok((s.policy != new_policy).then(|| (new_policy, policy::user_rules(&s.rules))))
+    });
+    if matches!(committed, Ok(Some(_))) {
+        // A newly locked guard can't stay off, as `set_enabled` enforces
+        let forced_on = state.lock().is_ok_and(|mut s| {
+            let off = !s.enabled && s.policy.settings.lock_enabled;
+            s.enabled |= off;
+            off
+        });
+        if forced_on {
+            crate::show_enabled(app, true);
+        }
+    }
+    let report = match committed {
+        Ok(None) => return,
+        Ok(Some(diagnostics)) => RulesReloaded {
+            applied: true,
+            error: None,
+            diagnostics,
+        },
+        Err(e) => RulesReloaded {
+            applied: false,
+            error: Some(e),
+            diagnostics: Vec::new(),
+        },
+    };
+    let _ = app.emit("rules-reloaded", &report);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// Serve canned responses, one per connection, and report each request's If-None-Match
+    fn stand_in_server(responses: Vec<String>) -> (String, mpsc::Receiver<Option<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/policy", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut if_none_match = None;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("if-none-match") {
+                            if_none_match = Some(value.trim().to_string());
+                        }
+                    }
+                }
+                tx.send(if_none_match).unwrap();
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        (url, rx)
+    }
+
+    fn ok_response(body: &str, etag: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nETag: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            etag,
+            body.len(),
+            body
+        )
+    }
+
+    fn not_modified() -> String {
+        "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n".to_string()
+    }
+
+    fn cache_path(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("clipguard-fetcher-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join(bundle::CACHE_FILE)
+    }
+
+    #[test]
+    fn rejects_plain_http_to_remote_hosts() {
+        assert!(check_url("http://policy.example.com/bundle").is_err());
+        assert!(check_url("http://localhost.example.com/bundle").is_err());
+        assert!(check_url("https://policy.example.com/bundle").is_ok());
+        assert!(check_url("http://127.0.0.1:8080/bundle").is_ok());
+    }
+
+    #[test]
+    fn accepts_signed_bundle_then_sends_etag() {
+        use base64::engine::general_purpose::STANDARD as BASE64;
+        use base64::Engine;
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let payload = br#"{"version":3,"settings":{"lock_enabled":true}}"#;
+        let body = serde_json::to_string(&SignedBundle {
+            payload: BASE64.encode(payload),
+            signature: BASE64.encode(key.sign(payload).to_bytes()),
+        })
+        .unwrap();
+        let (url, requests) = stand_in_server(vec![ok_response(&body, "\"v3\""), not_modified()]);
+        let trusted = vec![BASE64.encode(key.verifying_key().to_bytes())];
+        let cache = cache_path("signed");
+        let mut fetcher = Fetcher::new(&url, cache.clone(), trusted.clone()).unwrap();
+
+        match fetcher.poll() {
+            Ok(FetchOutcome::Updated(p)) => {
+                assert_eq!(p.version, 3);
+                assert!(p.settings.lock_enabled);
+            }
+            other => panic!("expected update, got {:?}", other),
+        }
+        assert!(matches!(fetcher.poll(), Ok(FetchOutcome::NotModified)));
+        assert_eq!(requests.recv().unwrap(), None);
+        assert_eq!(requests.recv().unwrap().as_deref(), Some("\"v3\""));
+        assert_eq!(
+            bundle::last_good(&cache, &trusted).map(|p| p.version),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn unsigned_bundle_is_rejected_and_etag_not_stored() {
+        let body = r#"{"payload":"e30=","signature":"AAAA"}"#;
+        let (url, requests) = stand_in_server(vec![
+            ok_response(body, "\"v1\""),
+            ok_response(body, "\"v1\""),
+        ]);
+        let key = "O2onvM62pC1io6jQKm8Nc2UyFXcd4kOmOsBIoYtZ2ik=".to_string();
+        let mut fetcher = Fetcher::new(&url, cache_path("unsigned"), vec![key]).unwrap();
+
+        assert!(matches!(fetcher.poll(), Err(FetchError::Rejected(_))));
+        assert!(matches!(fetcher.poll(), Err(FetchError::Rejected(_))));
+        assert_eq!(requests.recv().unwrap(), None);
+        assert_eq!(requests.recv().unwrap(), None);
+    }
+
+    #[test]
+    fn not_modified_and_server_errors() {
+        let (url, requests) = stand_in_server(vec![
+            not_modified(),
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_string(),
+        ]);
+        let mut fetcher = Fetcher::new(&url, cache_path("status"), Vec::new()).unwrap();
+        fetcher.etag = Some("\"v1\"".into());
+
+        assert!(matches!(fetcher.poll(), Ok(FetchOutcome::NotModified)));
+        assert!(matches!(fetcher.poll(), Err(FetchError::Network(_))));
+        assert_eq!(requests.recv().unwrap().as_deref(), Some("\"v1\""));
+    }
+}
@@ -4,24 +4,45 @@ use std::sync::{Arc, Mutex};
 
 use serde::Serialize;
 use tauri::{
-    menu::{Menu, MenuItemBuilder},
+    menu::{Menu, MenuItemBuilder, PredefinedMenuItem},
     tray::TrayIconBuilder,
     Emitter, Manager,
 };
 
+mod audit;
+mod autoclear;
+mod blocked;
+mod bundle;
+mod cli;
 #[cfg(target_os = "macos")]
 mod clipboard;
 #[cfg(target_os = "windows")]
 #[path = "clipboard_windows.rs"]
 mod clipboard;
-#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+#[cfg(target_os = "linux")]
+#[path = "clipboard_linux.rs"]
+mod clipboard;
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
 #[path = "clipboard_stub.rs"]
 mod clipboard;
 mod config;
+mod detectors;
+mod fetcher;
+mod lineage;
+mod overrides;
+mod policy;
+mod review;
 mod rules;
+mod ruleset;
+mod sanitize;
+mod schedule;
+mod snapshot;
+mod storage;
+mod watcher;
 
 use clipboard::ClipboardState;
-use rules::BlockRule;
+use overrides::{Override, OverrideTarget};
+use rules::{BlockRule, RuleDiagnostic};
 
 struct ToggleMenuItem(tauri::menu::MenuItem<tauri::Wry>);
 
@@ -38,6 +59,17 @@ fn get_clipboard_source(
     state.lock().ok().and_then(|s| s.last_copy_source.clone())
 }
 
+/// Show the guard's on/off state in the tray menu and the settings window
+fn show_enabled(app: &tauri::AppHandle, enabled: bool) {
+    let label = if enabled {
+        "Disable Guard"
+    } else {
+        "Enable Guard"
+    };
+    let _ = app.state::<ToggleMenuItem>().0.set_text(label);
+    let _ = app.emit("guard-toggled", enabled);
+}
+
 #[tauri::command]
 fn get_enabled(state: tauri::State<'_, Arc<Mutex<ClipboardState>>>) -> bool {
     state.lock().ok().map(|s| s.enabled).unwrap_or(true)
@@ -49,8 +81,11 @@ fn set_enabled(
     state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
     toggle: tauri::State<'_, ToggleMenuItem>,
     enabled: bool,
-) {
+) -> Result<(), String> {
     if let Ok(mut s) = state.lock() {
+        if !enabled && s.policy.settings.lock_enabled {
+            return Err("Your organization requires the guard to stay on".into());
+        }
         s.enabled = enabled;
     }
     let label = if enabled {
@@ -60,6 +95,7 @@ fn set_enabled(
     };
     let _ = toggle.0.set_text(label);
     let _ = app.emit("guard-toggled", enabled);
+    Ok(())
 }
 
 #[tauri::command]
@@ -76,14 +112,297 @@ fn set_rules(
     app: tauri::AppHandle,
     state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
     new_rules: Vec<BlockRule>,
+) -> Result<Vec<RuleDiagnostic>, String> {
+    commit_rules(&app, &state, |s| {
+        let user_rules = policy::apply_user_edit(&s.policy, &s.rules, &new_rules)?;
+        Ok(Some((s.policy.clone(), user_rules)))
+    })
+    .map(Option::unwrap_or_default)
+}
+
+/// Write the user's own rules to a TOML or YAML file for sharing
+#[tauri::command]
+fn export_rules(
+    state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
+    path: std::path::PathBuf,
 ) -> Result<(), String> {
-    rules::save(&app, &new_rules)?;
-    if let Ok(mut s) = state.lock() {
-        s.rules = new_rules;
+    let user_rules = state
+        .lock()
+        .map(|s| policy::user_rules(&s.rules))
+        .map_err(|e| e.to_string())?;
+    ruleset::write_file(&path, &user_rules)
+}
+
+/// What importing a TOML or YAML rule file would change, without applying it
+#[tauri::command]
+fn preview_import(
+    state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
+    path: std::path::PathBuf,
+    mode: ruleset::ImportMode,
+) -> Result<ruleset::ImportPreview, String> {
+    let imported = ruleset::read_file(&path)?;
+    let s = state.lock().map_err(|e| e.to_string())?;
+    ruleset::plan(&s.policy, &s.rules, imported, mode)
+}
+
+#[tauri::command]
+fn import_rules(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
+    path: std::path::PathBuf,
+    mode: ruleset::ImportMode,
+) -> Result<Vec<RuleDiagnostic>, String> {
+    let imported = ruleset::read_file(&path)?;
+    commit_rules(&app, &state, |s| {
+        let preview = ruleset::plan(&s.policy, &s.rules, imported, mode)?;
+        Ok(Some((s.policy.clone(), preview.rules)))
+    })
+    .map(Option::unwrap_or_default)
+}
+
+/// Merge, lint, persist and install a rule set. Shared by `set_rules` and policy updates.
+/// `change` picks the managed policy and user rules from the current state, or `None` to
+/// leave them be; the state stays locked until they're installed, so a concurrent edit,
+/// reload or sweep can't be overwritten with rules read before it.
+fn commit_rules(
+    app: &tauri::AppHandle,
+    state: &Arc<Mutex<ClipboardState>>,
+    change: impl FnOnce(&ClipboardState) -> Result<Option<(policy::Policy, Vec<BlockRule>)>, String>,
+) -> Result<Option<Vec<RuleDiagnostic>>, String> {
+    let mut s = state.lock().map_err(|e| e.to_string())?;
+    let Some((managed_policy, user_rules)) = change(&s)? else {
+        return Ok(None);
+    };
+    let previous = policy::user_rules(&s.rules);
+    let user_rules = rules::stamp(&previous, user_rules);
+    let merged = policy::merge(&managed_policy, user_rules.clone());
+    let diagnostics = rules::lint(&merged);
+    if let Some(err) = rules::first_error(&diagnostics) {
+        return Err(format!("Rule {}: {}", err.index + 1, err.message));
+    }
+    let unchanged = previous == user_rules;
+    if !unchanged {
+        rules::save(app, &user_rules)?;
+        s.rules_error = None;
+    }
+    s.policy = managed_policy;
+    s.rules = merged;
+    s.rule_diagnostics = diagnostics.clone();
+    s.recheck = true;
+    Ok(Some(diagnostics))
+}
+
+/// Settings pinned by the machine policy, so the UI can disable the matching controls
+#[tauri::command]
+fn get_policy_settings(
+    state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
+) -> policy::PolicySettings {
+    state
+        .lock()
+        .ok()
+        .map(|s| s.policy.settings.clone())
+        .unwrap_or_default()
+}
+
+/// How Block rules stop pastes, after any policy pin
+#[tauri::command]
+fn get_block_strategy(
+    state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
+) -> config::BlockStrategy {
+    state
+        .lock()
+        .ok()
+        .map(|s| config::block_strategy(&s.config, &s.policy.settings))
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+fn set_block_strategy(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
+    strategy: config::BlockStrategy,
+) -> Result<(), String> {
+    let mut s = state.lock().map_err(|e| e.to_string())?;
+    if s.policy.settings.block_strategy.is_some() {
+        return Err("Your organization sets how pastes are blocked".into());
+    }
+    let mut updated = s.config.clone();
+    updated.block_strategy = strategy;
+    config::save(&app, &updated)?;
+    s.config = updated;
+    s.config_error = None;
+    // Re-arm the frontmost app's block the new way
+    s.recheck = true;
+    Ok(())
+}
+
+/// Why rules.json couldn't be loaded at startup, if it couldn't
+#[tauri::command]
+fn get_rules_error(state: tauri::State<'_, Arc<Mutex<ClipboardState>>>) -> Option<String> {
+    state.lock().ok().and_then(|s| s.rules_error.clone())
+}
+
+/// Why config.json couldn't be loaded at startup, if it couldn't
+#[tauri::command]
+fn get_config_error(state: tauri::State<'_, Arc<Mutex<ClipboardState>>>) -> Option<String> {
+    state.lock().ok().and_then(|s| s.config_error.clone())
+}
+
+#[tauri::command]
+fn lint_rules(rules: Vec<BlockRule>) -> Vec<RuleDiagnostic> {
+    rules::lint(&rules)
+}
+
+#[tauri::command]
+fn get_rule_diagnostics(
+    state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
+) -> Vec<RuleDiagnostic> {
+    state
+        .lock()
+        .ok()
+        .map(|s| s.rule_diagnostics.clone())
+        .unwrap_or_default()
+}
+
+/// Rules that would take effect for a paste right now
+#[tauri::command]
+fn get_active_rules(state: tauri::State<'_, Arc<Mutex<ClipboardState>>>) -> Vec<BlockRule> {
+    state
+        .lock()
+        .ok()
+        .map(|s| rules::active_rules(&s.rules, &s.overrides))
+        .unwrap_or_default()
+}
+
+/// Active temporary allows and snoozes
+#[tauri::command]
+fn get_overrides(state: tauri::State<'_, Arc<Mutex<ClipboardState>>>) -> Vec<Override> {
+    let now = rules::unix_now();
+    state
+        .lock()
+        .ok()
+        .map(|s| {
+            s.overrides
+                .iter()
+                .filter(|o| o.is_active(now))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Allow a pair or snooze a rule until `until` (unix seconds)
+#[tauri::command]
+fn add_override(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
+    target: OverrideTarget,
+    until: u64,
+) -> Result<Override, String> {
+    if until <= rules::unix_now() {
+        return Err("The override would already have ended".into());
     }
+    let added = Override::new(target, until);
+    let updated = {
+        let mut s = state.lock().map_err(|e| e.to_string())?;
+        if s.policy.settings.lock_user_rules {
+            return Err("Your organization doesn't allow overriding rules".into());
+        }
+        if let OverrideTarget::SnoozeRule { rule_id } = &added.target {
+            match s.rules.iter().find(|r| r.id == *rule_id) {
+                Some(r) if r.managed => {
+                    return Err("Rules managed by your organization can't be snoozed".into())
+                }
+                Some(_) => {}
+                None => return Err(format!("No rule with id {}", rule_id)),
+            }
+        }
+        s.overrides.push(added.clone());
+        s.overrides.clone()
+    };
+    overrides::save(&app, &updated)?;
+    audit::record(&app, "override_added", serde_json::json!(added));
+    let _ = app.emit("overrides-changed", &updated);
+    Ok(added)
+}
+
+#[tauri::command]
+fn remove_override(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
+    id: String,
+) -> Result<(), String> {
+    let (removed, updated) = {
+        let mut s = state.lock().map_err(|e| e.to_string())?;
+        let Some(pos) = s.overrides.iter().position(|o| o.id == id) else {
+            return Ok(());
+        };
+        (s.overrides.remove(pos), s.overrides.clone())
+    };
+    overrides::save(&app, &updated)?;
+    audit::record(&app, "override_removed", serde_json::json!(removed));
+    let _ = app.emit("overrides-changed", &updated);
     Ok(())
 }
 
+/// The paste that was last blocked, if the user hasn't decided what to do with it yet
+#[tauri::command]
+fn get_pending_block(
+    state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
+) -> Option<blocked::PendingBlock> {
+    state.lock().ok().and_then(|s| s.pending_block.clone())
+}
+
+#[tauri::command]
+fn resolve_blocked_paste(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
+    choice: blocked::BlockChoice,
+) -> Result<(), String> {
+    blocked::resolve(&app, &state, choice)
+}
+
+/// The paste the review window is showing, if one is held
+#[tauri::command]
+fn get_paste_review(
+    state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
+) -> Option<review::PasteReview> {
+    state.lock().ok().and_then(|s| s.pending_review.clone())
+}
+
+#[tauri::command]
+fn resolve_paste_review(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
+    paste: bool,
+) {
+    review::resolve(&app, &state, paste);
+}
+
+#[tauri::command]
+fn evaluate_paste(
+    state: tauri::State<'_, Arc<Mutex<ClipboardState>>>,
+    source: Option<String>,
+    dest: String,
+    copy: Option<rules::CopyContext>,
+    content: Option<String>,
+) -> rules::Evaluation {
+    let (current_rules, current_overrides, current_policy) = state
+        .lock()
+        .ok()
+        .map(|s| (s.rules.clone(), s.overrides.clone(), s.policy.clone()))
+        .unwrap_or_default();
+    rules::evaluate_paste(
+        &current_rules,
+        &current_overrides,
+        source.as_deref(),
+        &dest,
+        &copy.unwrap_or_default(),
+        content.as_deref(),
+        &current_policy,
+    )
+}
+
 #[cfg(target_os = "macos")]
 fn read_app_bundle_info(path: &Path) -> Option<(String, String)> {
     let plist_path = path.join("Contents/Info.plist");
@@ -109,6 +428,18 @@ fn is_windows_platform() -> bool {
     cfg!(target_os = "windows")
 }
 
+/// Whether Confirm rules can hold a paste for review on this platform
+#[tauri::command]
+fn holds_pastes() -> bool {
+    clipboard::HOLDS_PASTES
+}
+
+/// Why rules can't see every app in front on this machine, if they can't
+#[tauri::command]
+fn app_detection_limit() -> Option<&'static str> {
+    clipboard::app_detection_limit()
+}
+
 #[cfg(target_os = "macos")]
 fn list_installed_apps() -> Vec<AppBundleInfo> {
     let dirs = [
@@ -289,6 +620,42 @@ fn open_accessibility_settings() {
     }
 }
 
+/// Run a CLI subcommand (e.g. `shell-init`, `inspect`) if one was given.
+/// Returns the process exit code, or `None` to start the app as usual.
+pub fn run_cli() -> Option<i32> {
+    let args: Vec<String> = std::env::args().collect();
+    cli::run(&args)
+}
+
+fn notify(app: &tauri::AppHandle, body: &str) {
+    use tauri_plugin_notification::NotificationExt;
+    let _ = app
+        .notification()
+        .builder()
+        .title("Clipboard Guard")
+        .body(body)
+        .show();
+}
+
+/// Tell the user a settings file was corrupt and has been restored from a backup
+fn report_recovery(app: &tauri::AppHandle, recovery: &storage::Recovery) {
+    notify(
+        app,
+        "A settings file was damaged and has been restored from a backup.",
+    );
+    let _ = app.emit("storage-recovered", recovery);
+}
+
+/// A policy bundle was refused; the last good policy stays in force
+fn report_policy_alert(app: &tauri::AppHandle, message: &str) {
+    eprintln!("policy bundle rejected: {}", message);
+    notify(
+        app,
+        "A clipboard policy update failed verification and was not applied.",
+    );
+    let _ = app.emit("policy-alert", message);
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -298,14 +665,36 @@ pub fn run() {
             None,
         ))
         .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_dialog::init())
         .invoke_handler(tauri::generate_handler![
             get_clipboard_source,
             get_enabled,
             set_enabled,
             get_rules,
             set_rules,
+            export_rules,
+            preview_import,
+            import_rules,
+            lint_rules,
+            get_rule_diagnostics,
+            get_rules_error,
+            get_config_error,
+            get_policy_settings,
+            get_block_strategy,
+            set_block_strategy,
+            get_active_rules,
+            get_overrides,
+            add_override,
+            remove_override,
+            get_pending_block,
+            resolve_blocked_paste,
+            get_paste_review,
+            resolve_paste_review,
+            evaluate_paste,
             list_apps,
             is_windows_platform,
+            holds_pastes,
+            app_detection_limit,
             check_accessibility,
             open_accessibility_settings,
         ])
@@ -314,31 +703,125 @@ pub fn run() {
             #[cfg(target_os = "macos")]
             app.set_activation_policy(tauri::ActivationPolicy::Accessory);
 
+            // Load config — on failure run with defaults but leave the user's file alone
+            let (mut app_config, config_error) = match config::load(&app.handle()) {
+                Ok(loaded) => {
+                    if let Some(recovery) = &loaded.recovery {
+                        report_recovery(&app.handle(), recovery);
+                    }
+                    (loaded.value, None)
+                }
+                Err(e) => {
+                    notify(
+                        &app.handle(),
+                        "Your settings could not be read. Using default settings until they are fixed.",
+                    );
+                    (config::Config::default(), Some(e))
+                }
+            };
             // Auto-enable launch at login on first run
-            let mut app_config = config::load(&app.handle());
-            if !app_config.autostart_initialized {
+            if config_error.is_none() && !app_config.autostart_initialized {
                 use tauri_plugin_autostart::ManagerExt;
                 let _ = app.autolaunch().enable();
                 app_config.autostart_initialized = true;
                 let _ = config::save(&app.handle(), &app_config);
             }
 
-            // Load rules
-            let loaded_rules = rules::load(&app.handle());
+            // Load rules — on failure run with defaults but leave the user's file alone
+            let (loaded_rules, rules_error) = match rules::load(&app.handle()) {
+                Ok(loaded) => {
+                    if let Some(recovery) = &loaded.recovery {
+                        report_recovery(&app.handle(), recovery);
+                    }
+                    (loaded.value, None)
+                }
+                Err(e) => {
+                    notify(
+                        &app.handle(),
+                        "Your rules could not be read. Using default rules until they are fixed.",
+                    );
+                    (rules::default_rules(), Some(e))
+                }
+            };
+
+            // Managed policy is layered above the user's rules
+            let last_good = storage::data_path(&app.handle(), policy::LAST_GOOD_FILE);
+            let (managed_policy, policy_error) = policy::load(last_good.as_deref());
+            match (policy_error, &last_good) {
+                (Some(e), _) => {
+                    eprintln!("policy: {}", e);
+                    notify(
+                        &app.handle(),
+                        "Your organization's clipboard policy could not be read. The last \
+                         policy stays in force, and the guard and your rules are locked.",
+                    );
+                }
+                (None, Some(path)) => {
+                    if let Err(e) = policy::remember(&managed_policy, path) {
+                        eprintln!("policy: {}", e);
+                    }
+                }
+                (None, None) => {}
+            }
+            let managed_policy = match storage::data_path(&app.handle(), bundle::CACHE_FILE) {
+                Some(cache) => {
+                    let enforced = bundle::enforce(managed_policy, &cache);
+                    if let Some(alert) = &enforced.alert {
+                        report_policy_alert(&app.handle(), alert);
+                    }
+                    enforced.policy
+                }
+                None => managed_policy,
+            };
+            let loaded_rules = policy::merge(&managed_policy, loaded_rules);
+            let rule_diagnostics = rules::lint(&loaded_rules);
 
             // Clipboard state — shared between tray menu and monitor thread
             let clip_state = Arc::new(Mutex::new(ClipboardState {
+                config: app_config,
+                config_error,
+                policy: managed_policy,
                 last_copy_source: None,
                 enabled: true,
                 rules: loaded_rules,
+                rule_diagnostics,
+                rules_error,
+                overrides: overrides::load(&app.handle()),
                 blocking_active: false,
+                pending_block: None,
+                recheck: false,
+                pending_review: None,
             }));
 
             // Build tray menu
             let toggle_item = MenuItemBuilder::with_id("toggle", "Disable Guard").build(app)?;
             let show_item = MenuItemBuilder::with_id("show", "Settings...").build(app)?;
             let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
-            let menu = Menu::with_items(app, &[&toggle_item, &show_item, &quit_item])?;
+            // Choices for the last blocked paste; enabled only while one is pending
+            let allow_once_item =
+                MenuItemBuilder::with_id("allow_once", "Allow Blocked Paste Once")
+                    .enabled(false)
+                    .build(app)?;
+            let always_allow_item =
+                MenuItemBuilder::with_id("always_allow", "Always Allow This Pair")
+                    .enabled(false)
+                    .build(app)?;
+            let keep_blocking_item = MenuItemBuilder::with_id("keep_blocking", "Keep Blocking")
+                .enabled(false)
+                .build(app)?;
+            let separator = PredefinedMenuItem::separator(app)?;
+            let menu = Menu::with_items(
+                app,
+                &[
+                    &toggle_item,
+                    &allow_once_item,
+                    &always_allow_item,
+                    &keep_blocking_item,
+                    &separator,
+                    &show_item,
+                    &quit_item,
+                ],
+            )?;
 
             // Build tray icon — use dedicated tray icon (simple silhouette)
             let tray_icon =
@@ -346,6 +829,11 @@ pub fn run() {
                     .expect("bundled tray icon");
 
             app.manage(ToggleMenuItem(toggle_item.clone()));
+            app.manage(blocked::BlockMenu(vec![
+                (blocked::BlockChoice::AllowOnce, allow_once_item),
+                (blocked::BlockChoice::AlwaysAllow, always_allow_item),
+                (blocked::BlockChoice::KeepBlocking, keep_blocking_item),
+            ]));
 
             let state_for_tray = clip_state.clone();
             let tray = TrayIconBuilder::new()
@@ -357,6 +845,10 @@ pub fn run() {
                 .on_menu_event(move |app, event| match event.id.as_ref() {
                     "toggle" => {
                         if let Ok(mut s) = state_for_tray.lock() {
+                            if s.enabled && s.policy.settings.lock_enabled {
+                                notify(app, "Your organization requires the guard to stay on.");
+                                return;
+                            }
                             s.enabled = !s.enabled;
                             let label = if s.enabled {
                                 "Disable Guard"
@@ -376,6 +868,16 @@ pub fn run() {
                             let _ = window.set_focus();
                         }
                     }
+                    id @ ("allow_once" | "always_allow" | "keep_blocking") => {
+                        let choice = match id {
+                            "allow_once" => blocked::BlockChoice::AllowOnce,
+                            "always_allow" => blocked::BlockChoice::AlwaysAllow,
+                            _ => blocked::BlockChoice::KeepBlocking,
+                        };
+                        if let Err(e) = blocked::resolve(app, &state_for_tray, choice) {
+                            notify(app, &e);
+                        }
+                    }
                     "quit" => {
                         app.exit(0);
                     }
@@ -385,6 +887,9 @@ pub fn run() {
 
             app.manage(tray);
             app.manage(clip_state.clone());
+            watcher::start(app.handle().clone(), clip_state.clone());
+            fetcher::start(app.handle().clone(), clip_state.clone());
+            overrides::start(app.handle().clone(), clip_state.clone());
             clipboard::start_clipboard_monitor(app.handle().clone(), clip_state);
 
             Ok(())
@@ -393,6 +898,13 @@ pub fn run() {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 api.prevent_close();
                 let _ = window.hide();
+                // Closing the review window drops the held paste
+                if window.label() == review::REVIEW_WINDOW {
+                    let app = window.app_handle();
+                    let state = app.state::<Arc<Mutex<ClipboardState>>>();
+                    review::resolve(app, &state, false);
+                    return;
+                }
                 #[cfg(target_os = "macos")]
                 let _ = window
                     .app_handle()
@@ -0,0 +1,85 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::policy::Policy;
+use crate::rules::{self, CopyContext};
+
+/// Most contents remembered; the oldest is forgotten first
+const MAX_ENTRIES: usize = 1000;
+
+/// The app content was first copied from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Origin {
+    pub app_id: Option<String>,
+    pub app_name: Option<String>,
+    /// Unix seconds
+    pub first_seen: u64,
+}
+
+/// What's remembered about one content
+struct Seen {
+    origin: Origin,
+    /// The most sensitive label it has picked up from any app it was copied from
+    label: Option<String>,
+}
+
+/// Where each recent clipboard content first came from and the label it carries, in
+/// memory only. Contents are keyed by a hash with a random per-run key, so the map can't
+/// be used to test guesses.
+#[derive(Default)]
+pub struct Lineage {
+    key: RandomState,
+    seen: HashMap<u64, Seen>,
+}
+
+impl Lineage {
+    /// Record a copy, filling in where its content first came from and its label.
+    /// `content` isn't read for concealed or transient copies: they're never remembered
+    /// and only get the label of the app they came from.
+    pub fn record(
+        &mut self,
+        copy: &mut CopyContext,
+        app_id: Option<&str>,
+        app_name: Option<&str>,
+        policy: &Policy,
+        content: impl FnOnce() -> Option<String>,
+    ) {
+        let app_label = app_id.and_then(|id| policy.label_for(id));
+        if copy.concealed || copy.transient {
+            copy.label = app_label;
+            return;
+        }
+        let Some(content) = content() else {
+            copy.label = app_label;
+            return;
+        };
+        let hash = self.key.hash_one(content);
+        if !self.seen.contains_key(&hash) && self.seen.len() >= MAX_ENTRIES {
+            let oldest = self
+                .seen
+                .iter()
+                .min_by_key(|(_, s)| s.origin.first_seen)
+                .map(|(h, _)| *h);
+            if let Some(oldest) = oldest {
+                self.seen.remove(&oldest);
+            }
+        }
+        let seen = self.seen.entry(hash).or_insert_with(|| Seen {
+            origin: Origin {
+                app_id: app_id.map(str::to_string),
+                app_name: app_name.map(str::to_string),
+                first_seen: rules::unix_now(),
+            },
+            label: None,
+        });
+        seen.label = match &policy.labels {
+            Some(labels) => labels.max(seen.label.take(), app_label),
+            None => None,
+        };
+        copy.origin = Some(seen.origin.clone());
+        copy.label = seen.label.clone();
+    }
+}
@@ -0,0 +1,223 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Emitter};
+
+use crate::audit;
+use crate::clipboard::ClipboardState;
+use crate::policy;
+use crate::rules;
+use crate::schedule;
+use crate::storage;
+use crate::watcher::RulesReloaded;
+
+pub const OVERRIDES_FILE: &str = "overrides.json";
+
+/// How often expired overrides and rules are cleared out and schedules checked
+const SWEEP_SECS: u64 = 15;
+
+/// What a temporary override lets through. Overrides only relax the user's own rules;
+/// managed rules still apply.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OverrideTarget {
+    /// Allow pastes from one app into another; no `from_app_id` means from any app
+    AllowPair {
+        from_app_id: Option<String>,
+        #[serde(default)]
+        from_app_name: Option<String>,
+        to_app_id: String,
+        #[serde(default)]
+        to_app_name: Option<String>,
+    },
+    /// Treat one rule as disabled
+    SnoozeRule { rule_id: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Override {
+    pub id: String,
+    #[serde(flatten)]
+    pub target: OverrideTarget,
+    /// Unix seconds; the override stops applying at this time
+    pub until: u64,
+    pub created_at: u64,
+}
+
+impl Override {
+    pub fn new(target: OverrideTarget, until: u64) -> Self {
+        Self {
+            id: rules::new_id(),
+            target,
+            until,
+            created_at: rules::unix_now(),
+        }
+    }
+
+    pub fn is_active(&self, now: u64) -> bool {
+        now < self.until
+    }
+
+    /// Whether this is a snooze of `rule`
+    pub fn snoozes(&self, rule: &rules::BlockRule) -> bool {
+        !rule.managed
+            && matches!(&self.target, OverrideTarget::SnoozeRule { rule_id } if *rule_id == rule.id)
+    }
+
+    /// Whether this override relaxes `rule` for a source→dest paste
+    pub fn covers(&self, rule: &rules::BlockRule, source: Option<&str>, dest: &str) -> bool {
+        if rule.managed {
+            return false;
+        }
+        match &self.target {
+            OverrideTarget::SnoozeRule { rule_id } => *rule_id == rule.id,
+            OverrideTarget::AllowPair {
+                from_app_id,
+                to_app_id,
+                ..
+            } => {
+                let from_matches = match from_app_id {
+                    None => true,
+                    Some(id) => source.is_some_and(|s| s.eq_ignore_ascii_case(id)),
+                };
+                from_matches && to_app_id.eq_ignore_ascii_case(dest)
+            }
+        }
+    }
+}
+
+/// Load saved overrides; a missing or unreadable file means none
+pub fn load(app: &AppHandle) -> Vec<Override> {
+    storage::data_path(app, OVERRIDES_FILE)
+        .map(|path| load_from(&path))
+        .unwrap_or_default()
+}
+
+/// Read overrides from an app data dir without a running app (used by the CLI)
+pub fn load_from_dir(dir: &Path) -> Vec<Override> {
+    let parse = |json: &str| serde_json::from_str(json).map_err(|e| e.to_string());
+    match storage::read_only(&dir.join(OVERRIDES_FILE), parse) {
+        Ok(overrides) => overrides.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("overrides: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn load_from(path: &Path) -> Vec<Override> {
+    let parse = |json: &str| serde_json::from_str(json).map_err(|e| e.to_string());
+    match storage::read_with_recovery(path, parse) {
+        Ok(Some(loaded)) => loaded.value,
+        Ok(None) => Vec::new(),
+        Err(e) => {
+            eprintln!("overrides: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+pub fn save(app: &AppHandle, overrides: &[Override]) -> Result<(), String> {
+    let Some(path) = storage::data_path(app, OVERRIDES_FILE) else {
+        return Err("no app data dir".into());
+    };
+    let json = serde_json::to_string_pretty(overrides).map_err(|e| e.to_string())?;
+    storage::write_atomic(&path, json.as_bytes())
+}
+
+/// Remove and return the overrides that have run out
+pub fn take_expired(overrides: &mut Vec<Override>, now: u64) -> Vec<Override> {
+    let (active, expired) = overrides.drain(..).partition(|o| o.is_active(now));
+    *overrides = active;
+    expired
+}
+
+/// Periodically drop expired overrides and rules past their `expires_at`, and
+/// re-evaluate the frontmost app when a schedule starts or stops applying
+pub fn start(app: AppHandle, state: Arc<Mutex<ClipboardState>>) {
+    thread::spawn(move || {
+        let mut scheduled = Vec::new();
+        loop {
+            sweep(&app, &state);
+            check_schedules(&state, &mut scheduled);
+            thread::sleep(Duration::from_secs(SWEEP_SECS));
+        }
+    });
+}
+
+/// The monitors only evaluate rules on an app switch, so ask for a recheck when the
+/// set of scheduled rules in force has changed since the last tick
+fn check_schedules(state: &Arc<Mutex<ClipboardState>>, scheduled: &mut Vec<String>) {
+    let local = schedule::local_now();
+    let Ok(mut s) = state.lock() else {
+        return;
+    };
+    let active: Vec<String> = s
+        .rules
+        .iter()
+        .filter(|r| r.schedule.as_ref().is_some_and(|w| w.is_active(&local)))
+        .map(|r| r.id.clone())
+        .collect();
+    if active != *scheduled {
+        s.recheck = true;
+        *scheduled = active;
+    }
+}
+
+fn sweep(app: &AppHandle, state: &Arc<Mutex<ClipboardState>>) {
+    let now = rules::unix_now();
+    let Some((expired, remaining)) = state.lock().ok().map(|mut s| {
+        let expired = take_expired(&mut s.overrides, now);
+        if !expired.is_empty() {
+            s.recheck = true;
+        }
+        (expired, s.overrides.clone())
+    }) else {
+        return;
+    };
+
+    if !expired.is_empty() {
+        if let Err(e) = save(app, &remaining) {
+            eprintln!("overrides: {}", e);
+        }
+        for o in &expired {
+            audit::record(app, "override_expired", json!(o));
+        }
+        let _ = app.emit("overrides-changed", &remaining);
+    }
+
+    let mut expired_rules = Vec::new();
+    let committed = crate::commit_rules(app, state, |s| {
+        let (expired, kept): (Vec<_>, Vec<_>) = policy::user_rules(&s.rules)
+            .into_iter()
+            .partition(|r| r.is_expired(now));
+        if expired.is_empty() {
+            return Ok(None);
+        }
+        expired_rules = expired;
+        Ok(Some((s.policy.clone(), kept)))
+    });
+    let report = match committed {
+        Ok(None) => return,
+        Ok(Some(diagnostics)) => {
+            for rule in &expired_rules {
+                audit::record(app, "rule_expired", json!(rule));
+            }
+            RulesReloaded {
+                applied: true,
+                error: None,
+                diagnostics,
+            }
+        }
+        Err(e) => RulesReloaded {
+            applied: false,
+            error: Some(e),
+            diagnostics: Vec::new(),
+        },
+    };
+    let _ = app.emit("rules-reloaded", &report);
+}
@@ -0,0 +1,416 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::BlockStrategy;
+use crate::detectors::DetectorConfig;
+use crate::rules::{self, BlockRule, RuleAction};
+use crate::storage;
+
+/// Settings an administrator can pin so users can't change them
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PolicySettings {
+    /// The guard can't be turned off from the UI or tray
+    #[serde(default)]
+    pub lock_enabled: bool,
+    /// Users can't add, edit or remove their own rules
+    #[serde(default)]
+    pub lock_user_rules: bool,
+    /// Pins how Block rules stop pastes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_strategy: Option<BlockStrategy>,
+}
+
+/// Where to poll for signed policy bundles
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RemotePolicy {
+    /// HTTPS URL serving a signed bundle (plain HTTP is only allowed for loopback hosts)
+    pub url: String,
+    #[serde(default = "default_poll_interval")]
+    pub interval_secs: u64,
+}
+
+fn default_poll_interval() -> u64 {
+    15 * 60
+}
+
+/// Clear the clipboard a while after a sensitive copy
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AutoClear {
+    pub after_secs: u64,
+    /// App groups whose copies are cleared, e.g. `"password-managers"`
+    #[serde(default)]
+    pub groups: Vec<String>,
+    /// Also clear copies a secret detector fires on
+    #[serde(default)]
+    pub secrets: bool,
+}
+
+/// Warn before pasting content copied a while ago
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StalePaste {
+    pub after_mins: u64,
+    /// App groups it applies to pasting into; empty for every app
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+/// Sensitivity labels for copies and the app groups that hand them out
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Labels {
+    /// Least sensitive first, e.g. `["public", "internal", "confidential", "secret"]`
+    pub order: Vec<String>,
+    /// The label copies from each app group get, e.g. `"hr": "confidential"`
+    #[serde(default)]
+    pub groups: BTreeMap<String, String>,
+}
+
+impl Labels {
+    /// Position of `label` in `order`; higher is more sensitive
+    pub fn rank(&self, label: &str) -> Option<usize> {
+        self.order
+            .iter()
+            .position(|l| l.eq_ignore_ascii_case(label))
+    }
+
+    /// The more sensitive of two labels; unknown labels lose to known ones
+    pub fn max(&self, a: Option<String>, b: Option<String>) -> Option<String> {
+        match (a, b) {
+            (Some(a), Some(b)) if self.rank(&b) > self.rank(&a) => Some(b),
+            (Some(a), _) => Some(a),
+            (None, b) => b,
+        }
+    }
+}
+
+/// Apps one trust zone holds: app ids, or `"@group"` for every app in a policy app group
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Zone {
+    pub name: String,
+    pub apps: Vec<String>,
+}
+
+/// What happens to pastes from one zone into another
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ZoneFlow {
+    /// Zone name, or `"*"` for any app
+    pub from: String,
+    /// Zone name, or `"*"` for any app
+    pub to: String,
+    pub action: RuleAction,
+}
+
+impl ZoneFlow {
+    pub fn covers(&self, from_zone: Option<&str>, to_zone: Option<&str>) -> bool {
+        let side = |pattern: &str, zone: Option<&str>| {
+            pattern == "*" || zone.is_some_and(|z| z.eq_ignore_ascii_case(pattern))
+        };
+        side(&self.from, from_zone) && side(&self.to, to_zone)
+    }
+}
+
+/// Apps placed in trust zones, and the flows allowed between them
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TrustZones {
+    /// An app in several zones belongs to the first one listed
+    #[serde(default)]
+    pub zones: Vec<Zone>,
+    /// The first flow covering a paste decides it
+    #[serde(default)]
+    pub flows: Vec<ZoneFlow>,
+    /// For pastes between two different zones that no flow covers; unset lets them through
+    #[serde(default)]
+    pub default_action: Option<RuleAction>,
+}
+
+/// Machine-level policy, layered above the per-user rules.json
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Policy {
+    #[serde(default)]
+    pub rules: Vec<BlockRule>,
+    #[serde(default)]
+    pub settings: PolicySettings,
+    /// Base64 Ed25519 public keys allowed to sign policy bundles
+    #[serde(default)]
+    pub trusted_keys: Vec<String>,
+    /// Named sets of app ids, e.g. `"password-managers"`
+    #[serde(default)]
+    pub app_groups: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub detectors: DetectorConfig,
+    #[serde(default)]
+    pub remote: Option<RemotePolicy>,
+    #[serde(default)]
+    pub auto_clear: Option<AutoClear>,
+    #[serde(default)]
+    pub labels: Option<Labels>,
+    #[serde(default)]
+    pub trust_zones: Option<TrustZones>,
+    #[serde(default)]
+    pub stale_paste: Option<StalePaste>,
+    /// Version of the signed bundle in force, if any
+    #[serde(default, skip_deserializing)]
+    pub bundle_version: Option<u64>,
+}
+
+impl Policy {
+    /// Whether `app_id` is listed under the app group `group`
+    pub fn in_group(&self, group: &str, app_id: &str) -> bool {
+        self.app_groups
+            .get(group)
+            .is_some_and(|apps| apps.iter().any(|a| a.eq_ignore_ascii_case(app_id)))
+    }
+
+    /// The trust zone `app_id` is in, if any
+    pub fn zone_of(&self, app_id: &str) -> Option<&str> {
+        let zones = self.trust_zones.as_ref()?;
+        zones
+            .zones
+            .iter()
+            .find(|zone| {
+                zone.apps.iter().any(|app| match app.strip_prefix('@') {
+                    Some(group) => self.in_group(group, app_id),
+                    None => app.eq_ignore_ascii_case(app_id),
+                })
+            })
+            .map(|zone| zone.name.as_str())
+    }
+
+    /// `self` with the guard and the user's rules locked, for when policy.json can't be trusted
+    pub fn locked(mut self) -> Policy {
+        self.settings.lock_enabled = true;
+        self.settings.lock_user_rules = true;
+        self
+    }
+
+    /// The label copies from `app_id` get: the most sensitive one among its groups
+    pub fn label_for(&self, app_id: &str) -> Option<String> {
+        let labels = self.labels.as_ref()?;
+        labels
+            .groups
+            .iter()
+            .filter(|(group, _)| self.in_group(group, app_id))
+            .map(|(_, label)| label.clone())
+            .fold(None, |a, b| labels.max(a, Some(b)))
+    }
+}
+
+/// Where the managed policy lives; only writable by administrators
+pub fn policy_path() -> PathBuf {
+    if cfg!(target_os = "macos") {
+        PathBuf::from("/Library/Application Support/Clipguard/policy.json")
+    } else if cfg!(target_os = "windows") {
+        std::env::var_os("ProgramData")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"))
+            .join(r"Clipguard\policy.json")
+    } else {
+        PathBuf::from("/etc/clipguard/policy.json")
+    }
+}
+
+/// Copy of the last policy.json that loaded, kept in the app data dir
+pub const LAST_GOOD_FILE: &str = "policy.last.json";
+
+/// Read a policy file. `Ok(None)` when it doesn't exist.
+fn read(path: &Path) -> Result<Option<Policy>, String> {
+    let json = match fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Could not read {}: {}", path.display(), e)),
+    };
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Load the managed policy; the default when the machine has none. If policy.json exists
+/// but can't be read or parsed, the copy at `last_good` is used instead, locked, so a
+/// corrupt policy never lifts managed rules or locks. The error comes back for reporting.
+pub fn load(last_good: Option<&Path>) -> (Policy, Option<String>) {
+    load_from(&policy_path(), last_good)
+}
+
+fn load_from(path: &Path, last_good: Option<&Path>) -> (Policy, Option<String>) {
+    match read(path) {
+        Ok(policy) => (policy.unwrap_or_default(), None),
+        Err(e) => {
+            let previous = last_good.and_then(|p| read(p).ok().flatten());
+            (previous.unwrap_or_default().locked(), Some(e))
+        }
+    }
+}
+
+/// Keep `policy` at `last_good` for `load` to fall back on; only written when it changed
+pub fn remember(policy: &Policy, last_good: &Path) -> Result<(), String> {
+    if read(last_good).ok().flatten().as_ref() == Some(policy) {
+        return Ok(());
+    }
+    let json = serde_json::to_string_pretty(policy).map_err(|e| e.to_string())?;
+    storage::write_atomic(last_good, json.as_bytes())
+}
+
+/// Managed rules first so they always win, then the user's own rules.
+/// Invalid managed rules are dropped so they can't block the user from saving.
+/// Managed rules without an id get one from their position in the policy.
+pub fn merge(policy: &Policy, user_rules: Vec<BlockRule>) -> Vec<BlockRule> {
+    policy
+        .rules
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| rules::is_valid(r))
+        .map(|(i, r)| {
+            let mut r = r.clone();
+            r.managed = true;
+            if r.id.is_empty() {
+                r.id = format!("managed-{}", i + 1);
+            }
+            r
+        })
+        .chain(user_rules.into_iter().map(|mut r| {
+            r.managed = false;
+            r
+        }))
+        .collect()
+}
+
+/// The rules that belong in the user's rules.json
+pub fn user_rules(rules: &[BlockRule]) -> Vec<BlockRule> {
+    rules.iter().filter(|r| !r.managed).cloned().collect()
+}
+
+/// Check an edited rule list against the policy and return the user's part of it.
+/// Managed rules must come back unchanged; `lock_user_rules` freezes the rest.
+pub fn apply_user_edit(
+    policy: &Policy,
+    current: &[BlockRule],
+    proposed: &[BlockRule],
+) -> Result<Vec<BlockRule>, String> {
+    let expected: Vec<&BlockRule> = current.iter().filter(|r| r.managed).collect();
+    let returned: Vec<&BlockRule> = proposed.iter().filter(|r| r.managed).collect();
+    if expected != returned {
+        return Err("Rules managed by your organization can't be changed or removed".into());
+    }
+
+    let edited = user_rules(proposed);
+    if policy.settings.lock_user_rules && edited != user_rules(current) {
+        return Err("Your organization doesn't allow editing rules".into());
+    }
+    Ok(edited)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("clipguard-policy-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn rule(to: &str, action: RuleAction) -> BlockRule {
+        BlockRule::new(None, Some(to.into()), action)
+    }
+
+    fn managed_policy() -> Policy {
+        Policy {
+            rules: vec![rule("com.chat.app", RuleAction::Block)],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn corrupt_policy_falls_back_to_the_last_good_copy_locked() {
+        let dir = scratch("corrupt");
+        let path = dir.join("policy.json");
+        let last_good = dir.join(LAST_GOOD_FILE);
+
+        assert_eq!(
+            load_from(&path, Some(&last_good)),
+            (Policy::default(), None)
+        );
+        fs::write(&path, serde_json::to_string(&managed_policy()).unwrap()).unwrap();
+        let (loaded, error) = load_from(&path, Some(&last_good));
+        assert_eq!((&loaded, error), (&managed_policy(), None));
+        remember(&loaded, &last_good).unwrap();
+
+        fs::write(&path, "{ not json").unwrap();
+        let (fallback, error) = load_from(&path, Some(&last_good));
+        assert!(error.is_some());
+        assert_eq!(fallback.rules, managed_policy().rules);
+        assert!(fallback.settings.lock_enabled && fallback.settings.lock_user_rules);
+
+        // Without a copy the locks still apply
+        let (fallback, error) = load_from(&path, None);
+        assert!(error.is_some());
+        assert_eq!(fallback, Policy::default().locked());
+    }
+
+    #[test]
+    fn merge_puts_valid_managed_rules_first() {
+        let policy = Policy {
+            rules: vec![
+                BlockRule::new(None, None, RuleAction::Block),
+                BlockRule {
+                    id: "keep".into(),
+                    ..rule("com.mail", RuleAction::Notify)
+                },
+                rule("com.chat.app", RuleAction::Block),
+            ],
+            ..Default::default()
+        };
+        let user = BlockRule {
+            managed: true,
+            ..rule("com.notes", RuleAction::Allow)
+        };
+        let merged = merge(&policy, vec![user]);
+        let ids: Vec<&str> = merged.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["keep", "managed-3", ""]);
+        let managed: Vec<bool> = merged.iter().map(|r| r.managed).collect();
+        assert_eq!(managed, [true, true, false]);
+        assert_eq!(user_rules(&merged), [rule("com.notes", RuleAction::Allow)]);
+    }
+
+    #[test]
+    fn user_edits_keep_managed_rules_intact() {
+        let policy = managed_policy();
+        let current = merge(&policy, vec![rule("com.notes", RuleAction::Notify)]);
+
+        let mut added = current.clone();
+        added.push(rule("com.mail", RuleAction::Block));
+        let edited = apply_user_edit(&policy, &current, &added).unwrap();
+        assert_eq!(edited, user_rules(&added));
+
+        let removed = current[1..].to_vec();
+        assert!(apply_user_edit(&policy, &current, &removed).is_err());
+        let mut changed = current.clone();
+        changed[0].action = RuleAction::Allow;
+        assert!(apply_user_edit(&policy, &current, &changed).is_err());
+        // Claiming a user rule is managed doesn't get it past the check
+        let mut claimed = current.clone();
+        claimed[1].managed = true;
+        assert!(apply_user_edit(&policy, &current, &claimed).is_err());
+    }
+
+    #[test]
+    fn locked_user_rules_only_accept_no_change() {
+        let policy = managed_policy().locked();
+        let current = merge(&policy, vec![rule("com.notes", RuleAction::Notify)]);
+        assert_eq!(
+            apply_user_edit(&policy, &current, &current),
+            Ok(user_rules(&current))
+        );
+        let mut edited = current.clone();
+        edited[1].action = RuleAction::Block;
+        assert_eq!(
+            apply_user_edit(&policy, &current, &edited),
+            Err("Your organization doesn't allow editing rules".into())
+        );
+    }
+}
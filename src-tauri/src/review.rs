@@ -0,0 +1,113 @@
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::audit;
+use crate::clipboard::{self, ClipboardState};
+use crate::detectors::{self, Finding};
+
+pub const REVIEW_WINDOW: &str = "review";
+
+/// Longest preview shown in the review window, in characters
+const PREVIEW_CHARS: usize = 4000;
+
+/// A paste held by a Confirm rule, waiting for the user to release or drop it
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PasteReview {
+    pub source_app_id: Option<String>,
+    pub source_app_name: Option<String>,
+    pub dest_app_id: String,
+    pub dest_app_name: Option<String>,
+    pub rule_id: Option<String>,
+    /// Clipboard text with hidden characters made visible
+    pub preview: String,
+    pub truncated: bool,
+    pub findings: Vec<Finding>,
+    /// Marked as a password by the source app, so there's no preview or findings
+    #[serde(default)]
+    pub concealed: bool,
+}
+
+impl PasteReview {
+    /// Fill in the preview and findings for the text on the clipboard. `content` isn't
+    /// read for concealed copies.
+    pub fn with_content(
+        mut self,
+        content: impl FnOnce() -> Option<String>,
+        state: &Arc<Mutex<ClipboardState>>,
+    ) -> Self {
+        if self.concealed {
+            return self;
+        }
+        let Some(content) = content() else {
+            return self;
+        };
+        let detector_config = state
+            .lock()
+            .ok()
+            .map(|s| s.policy.detectors.clone())
+            .unwrap_or_default();
+        self.findings = detectors::scan_with(&content, &detector_config);
+        let revealed = detectors::reveal(&content);
+        self.truncated = revealed.chars().count() > PREVIEW_CHARS;
+        self.preview = revealed.chars().take(PREVIEW_CHARS).collect();
+        self
+    }
+}
+
+/// Hold the paste for review and show the review window on top of the destination app
+pub fn open(app: &AppHandle, state: &Arc<Mutex<ClipboardState>>, review: PasteReview) {
+    if let Ok(mut s) = state.lock() {
+        s.pending_review = Some(review.clone());
+    }
+    if let Some(window) = app.get_webview_window(REVIEW_WINDOW) {
+        let _ = window.show();
+        let _ = window.set_focus();
+    } else {
+        let built = WebviewWindowBuilder::new(
+            app,
+            REVIEW_WINDOW,
+            WebviewUrl::App("index.html?view=review".into()),
+        )
+        .title("Review Paste")
+        .inner_size(420.0, 360.0)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(false)
+        .center()
+        .build();
+        if let Err(e) = built {
+            eprintln!("review window: {}", e);
+        }
+    }
+    let _ = app.emit("paste-review", Some(&review));
+}
+
+/// Release the held paste into the destination app, or drop it
+pub fn resolve(app: &AppHandle, state: &Arc<Mutex<ClipboardState>>, paste: bool) {
+    let Some(review) = state.lock().ok().and_then(|mut s| s.pending_review.take()) else {
+        return;
+    };
+    if let Some(window) = app.get_webview_window(REVIEW_WINDOW) {
+        let _ = window.hide();
+    }
+    // Only a backend that held the paste can replay it
+    let pasted = paste && clipboard::HOLDS_PASTES;
+    if pasted {
+        clipboard::release_paste(&review.dest_app_id);
+    }
+    audit::record(
+        app,
+        "paste_review",
+        json!({
+            "pasted": pasted,
+            "source_app_id": review.source_app_id,
+            "dest_app_id": review.dest_app_id,
+            "rule_id": review.rule_id,
+            "findings": review.findings.iter().map(|f| &f.detector).collect::<Vec<_>>(),
+        }),
+    );
+    let _ = app.emit("paste-review", None::<PasteReview>);
+}
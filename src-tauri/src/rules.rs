@@ -1,28 +1,228 @@
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
-use tauri::Manager;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::detectors::{self, Finding};
+use crate::lineage::Origin;
+use crate::overrides::Override;
+use crate::policy::Policy;
+use crate::sanitize::Transform;
+use crate::schedule::{self, Schedule};
+use crate::storage::{self, Loaded};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum RuleAction {
     Notify,
     Block,
+    /// Hold the paste until the user reviews it
+    Confirm,
+    /// Rewrite the clipboard while the destination has focus
+    Sanitize,
+    /// Mask detected secrets in the clipboard while the destination has focus
+    Redact,
+    /// Explicitly let the pair through, ahead of later rules
+    Allow,
+}
+
+/// Which source app a rule's `from_app_id` is compared with
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceMatch {
+    /// The app the clipboard was last copied from
+    #[default]
+    Immediate,
+    /// The app the content was first copied from, before any re-copies
+    Original,
+    Either,
+}
+
+impl SourceMatch {
+    pub fn is_immediate(&self) -> bool {
+        *self == SourceMatch::Immediate
+    }
+
+    /// `self` matches the source in at least every case `inner` does
+    fn covers(self, inner: SourceMatch) -> bool {
+        self == inner || self == SourceMatch::Either
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct BlockRule {
+    /// Stable identifier, assigned when the rule is first saved
+    #[serde(default)]
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     pub from_app_id: Option<String>,
     pub from_app_name: Option<String>,
     pub to_app_id: Option<String>,
     pub to_app_name: Option<String>,
+    /// Rule-file group `from_app_id` was expanded from, so an export writes `"@group"` again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_group: Option<String>,
+    /// Rule-file group `to_app_id` was expanded from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_group: Option<String>,
+    /// Whether `from_app_id` is checked against the immediate or the original source
+    #[serde(default, skip_serializing_if = "SourceMatch::is_immediate")]
+    pub match_source: SourceMatch,
     pub action: RuleAction,
+    /// Disabled rules are kept but never match
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Unix seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+    /// Unix seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified_at: Option<u64>,
+    /// Unix seconds; the rule is removed once this passes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+    /// Only applies inside these local-time windows
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<Schedule>,
+    /// Rewrites for Sanitize rules; empty means the default set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<Transform>,
+    /// Only match copies the source app did (true) or didn't (false) mark as a password
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concealed: Option<bool>,
+    /// Only match copies the source app did (true) or didn't (false) mark as short-lived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transient: Option<bool>,
+    /// Only match copies labelled at least this sensitive, in the policy's label order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_label: Option<String>,
+    /// Only match pastes into apps outside this policy app group
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outside_group: Option<String>,
+    /// Only match copies made at most this many seconds ago
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<u64>,
+    /// Only match copies made at least this many seconds ago
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_age: Option<u64>,
+    /// Comes from the machine policy; read-only for the user and never saved to rules.json
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub managed: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl BlockRule {
+    /// An enabled, unsaved rule between two apps, without metadata
+    pub fn new(from_app_id: Option<String>, to_app_id: Option<String>, action: RuleAction) -> Self {
+        Self {
+            id: String::new(),
+            name: None,
+            description: None,
+            from_app_id,
+            from_app_name: None,
+            to_app_id,
+            to_app_name: None,
+            from_group: None,
+            to_group: None,
+            match_source: SourceMatch::Immediate,
+            action,
+            enabled: true,
+            tags: Vec::new(),
+            created_at: None,
+            modified_at: None,
+            expires_at: None,
+            schedule: None,
+            transforms: Vec::new(),
+            concealed: None,
+            transient: None,
+            min_label: None,
+            outside_group: None,
+            max_age: None,
+            min_age: None,
+            managed: false,
+        }
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|t| t <= now)
+    }
+
+    /// Same settings, ignoring id, timestamps and the groups the apps came from
+    pub fn same_content(&self, other: &BlockRule) -> bool {
+        let strip = |r: &BlockRule| BlockRule {
+            id: String::new(),
+            created_at: None,
+            modified_at: None,
+            from_group: None,
+            to_group: None,
+            ..r.clone()
+        };
+        strip(self) == strip(other)
+    }
 }
 
-fn rules_path(app: &tauri::AppHandle) -> Option<PathBuf> {
-    app.path().app_data_dir().ok().map(|d| d.join("rules.json"))
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
+/// A new rule or override id: creation time in ms plus a counter, so ids made in the same ms differ
+pub fn new_id() -> String {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed) & 0xffff;
+    format!("r{:x}{:04x}", millis, n)
+}
+
+/// Give new rules an id and timestamps, and bump `modified_at` on rules whose settings
+/// changed since `previous`. Duplicate ids are treated as new rules.
+pub fn stamp(previous: &[BlockRule], proposed: Vec<BlockRule>) -> Vec<BlockRule> {
+    let now = unix_now();
+    let mut seen = HashSet::new();
+    proposed
+        .into_iter()
+        .map(|mut rule| {
+            if rule.id.is_empty() || seen.contains(&rule.id) {
+                rule.id = new_id();
+            }
+            seen.insert(rule.id.clone());
+            match previous.iter().find(|p| p.id == rule.id) {
+                Some(old) => {
+                    rule.created_at = old.created_at;
+                    rule.modified_at = if old.same_content(&rule) {
+                        old.modified_at
+                    } else {
+                        Some(now)
+                    };
+                }
+                None => {
+                    rule.created_at = rule.created_at.or(Some(now));
+                    rule.modified_at = rule.modified_at.or(Some(now));
+                }
+            }
+            rule
+        })
+        .collect()
+}
+
+const RULES_FILE: &str = "rules.json";
+
 pub fn default_rules() -> Vec<BlockRule> {
     let terminals = [
         ("com.apple.Terminal", "Terminal"),
@@ -37,62 +237,1204 @@ pub fn default_rules() -> Vec<BlockRule> {
     terminals
         .iter()
         .map(|(id, name)| BlockRule {
-            from_app_id: None,
-            from_app_name: None,
-            to_app_id: Some(id.to_string()),
+            id: new_id(),
             to_app_name: Some(name.to_string()),
-            action: RuleAction::Notify,
+            ..BlockRule::new(None, Some(id.to_string()), RuleAction::Notify)
         })
         .collect()
 }
 
-pub fn load(app: &tauri::AppHandle) -> Vec<BlockRule> {
-    let Some(path) = rules_path(app) else {
-        return default_rules();
+/// Current rules.json schema version. Bump it and append to `MIGRATIONS` when the format changes.
+pub const SCHEMA_VERSION: u64 = 2;
+
+/// On-disk envelope for rules.json
+#[derive(Serialize, Deserialize)]
+struct RulesFile {
+    version: u64,
+    rules: Vec<BlockRule>,
+}
+
+type Migration = fn(Value) -> Result<Value, String>;
+
+/// `MIGRATIONS[n]` upgrades a version `n` document to version `n + 1`
+const MIGRATIONS: &[Migration] = &[migrate_v0_to_v1, migrate_v1_to_v2];
+
+/// v0 was a bare array of rules
+fn migrate_v0_to_v1(value: Value) -> Result<Value, String> {
+    Ok(json!({ "version": 1, "rules": value }))
+}
+
+/// v2 gives every rule an id; the other metadata fields have serde defaults
+fn migrate_v1_to_v2(mut value: Value) -> Result<Value, String> {
+    let rules = value
+        .get_mut("rules")
+        .and_then(Value::as_array_mut)
+        .ok_or("missing \"rules\" array")?;
+    for rule in rules {
+        let rule = rule.as_object_mut().ok_or("rule is not an object")?;
+        if !rule.get("id").is_some_and(|id| id.is_string()) {
+            rule.insert("id".into(), json!(new_id()));
+        }
+    }
+    value["version"] = json!(2);
+    Ok(value)
+}
+
+fn schema_version(value: &Value) -> Result<u64, String> {
+    match value {
+        Value::Array(_) => Ok(0),
+        Value::Object(map) => map
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| "missing \"version\" field".to_string()),
+        _ => Err("expected an object or an array".into()),
+    }
+}
+
+/// Load rules, migrating older files in place. A corrupt file is replaced by its newest valid
+/// backup; if there is none the file is left alone so the caller can report the error.
+pub fn load(app: &tauri::AppHandle) -> Result<Loaded<Vec<BlockRule>>, String> {
+    let Some(path) = storage::data_path(app, RULES_FILE) else {
+        return Ok(Loaded {
+            value: default_rules(),
+            recovery: None,
+        });
     };
-    match fs::read_to_string(&path) {
-        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|_| default_rules()),
-        Err(_) => default_rules(),
+    load_from(&path)
+}
+
+/// Read rules from an app data dir without a running app (used by the CLI). Older files
+/// are migrated in memory only; the app migrates them on disk when it next loads them.
+pub fn load_from_dir(dir: &Path) -> Result<Vec<BlockRule>, String> {
+    let loaded = storage::read_only(&dir.join(RULES_FILE), parse_and_migrate)?;
+    Ok(loaded.map_or_else(default_rules, |(rules, _)| rules))
+}
+
+fn load_from(path: &Path) -> Result<Loaded<Vec<BlockRule>>, String> {
+    let Some(loaded) = storage::read_with_recovery(path, parse_and_migrate)? else {
+        return Ok(Loaded {
+            value: default_rules(),
+            recovery: None,
+        });
+    };
+    let (rules, version) = loaded.value;
+    if version < SCHEMA_VERSION {
+        // Keep the pre-migration file around before rewriting it
+        let backup = storage::sibling(path, &format!(".v{}.bak", version));
+        fs::copy(path, &backup).map_err(|e| e.to_string())?;
+        write_to(path, &rules)?;
     }
+    Ok(Loaded {
+        value: rules,
+        recovery: loaded.recovery,
+    })
+}
+
+/// Parse any known schema version, returning the rules and the version found on disk
+fn parse_and_migrate(json: &str) -> Result<(Vec<BlockRule>, u64), String> {
+    let mut value: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let version = schema_version(&value)?;
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "version {} is newer than this app supports ({})",
+            version, SCHEMA_VERSION
+        ));
+    }
+    for migration in &MIGRATIONS[version as usize..] {
+        value = migration(value)?;
+    }
+    let file: RulesFile = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    Ok((file.rules, version))
+}
+
+/// Read rules.json as it is on disk, without migrating it or falling back to backups
+pub fn read(app: &tauri::AppHandle) -> Result<Vec<BlockRule>, String> {
+    let path = storage::data_path(app, RULES_FILE).ok_or("no app data dir")?;
+    let json = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    parse_and_migrate(&json)
+        .map(|(rules, _)| rules)
+        .map_err(|e| format!("{}: {}", path.display(), e))
 }
 
 pub fn save(app: &tauri::AppHandle, rules: &[BlockRule]) -> Result<(), String> {
-    let Some(path) = rules_path(app) else {
+    let Some(path) = storage::data_path(app, RULES_FILE) else {
         return Err("no app data dir".into());
     };
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-    let json = serde_json::to_string_pretty(rules).map_err(|e| e.to_string())?;
-    fs::write(&path, json).map_err(|e| e.to_string())
+    write_to(&path, rules)
 }
 
-/// Check if a rule is valid: at least one side must specify an app
-#[allow(dead_code)]
+fn write_to(path: &Path, rules: &[BlockRule]) -> Result<(), String> {
+    let file = RulesFile {
+        version: SCHEMA_VERSION,
+        rules: rules.to_vec(),
+    };
+    let json = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    storage::write_atomic(path, json.as_bytes())
+}
+
+/// Check if a rule is valid: at least one side must specify an app, unless the rule is
+/// narrowed some other way: to concealed, transient, labelled, old or recent copies, or
+/// to pastes outside an app group
 pub fn is_valid(rule: &BlockRule) -> bool {
-    rule.from_app_id.is_some() || rule.to_app_id.is_some()
+    rule.from_app_id.is_some()
+        || rule.to_app_id.is_some()
+        || rule.concealed == Some(true)
+        || rule.transient == Some(true)
+        || rule.min_label.is_some()
+        || rule.outside_group.is_some()
+        || rule.max_age.is_some()
+        || rule.min_age.is_some()
+}
+
+/// Whether the rule only matches some pastes between its apps
+fn has_conditions(rule: &BlockRule) -> bool {
+    rule.concealed.is_some()
+        || rule.transient.is_some()
+        || rule.min_label.is_some()
+        || rule.outside_group.is_some()
+        || rule.max_age.is_some()
+        || rule.min_age.is_some()
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticLevel {
+    Error,
+    Warning,
+}
+
+/// A lint finding for the rule at `index`, optionally pointing at the rule it clashes with
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RuleDiagnostic {
+    pub index: usize,
+    pub level: DiagnosticLevel,
+    pub code: String,
+    pub message: String,
+    pub related: Option<usize>,
+}
+
+impl RuleDiagnostic {
+    fn new(
+        index: usize,
+        level: DiagnosticLevel,
+        code: &str,
+        message: String,
+        related: Option<usize>,
+    ) -> Self {
+        Self {
+            index,
+            level,
+            code: code.to_string(),
+            message,
+            related,
+        }
+    }
+}
+
+fn same_app(a: &Option<String>, b: &Option<String>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+        _ => false,
+    }
 }
 
-/// Find first matching rule for a source→dest pair
-pub fn matches_rule(
+/// `outer` matches at least every app that `inner` matches
+fn covers(outer: &Option<String>, inner: &Option<String>) -> bool {
+    outer.is_none() || same_app(outer, inner)
+}
+
+/// Report invalid rules, duplicates, conflicting actions and rules that can never win
+pub fn lint(rules: &[BlockRule]) -> Vec<RuleDiagnostic> {
+    let mut diagnostics = Vec::new();
+    for (i, rule) in rules.iter().enumerate() {
+        if !is_valid(rule) {
+            diagnostics.push(RuleDiagnostic::new(
+                i,
+                DiagnosticLevel::Error,
+                "invalid",
+                "At least one app or condition must be specified".into(),
+                None,
+            ));
+            continue;
+        }
+        if let Some(Err(e)) = rule.schedule.as_ref().map(Schedule::validate) {
+            diagnostics.push(RuleDiagnostic::new(
+                i,
+                DiagnosticLevel::Error,
+                "schedule",
+                e,
+                None,
+            ));
+            continue;
+        }
+        if !rule.transforms.is_empty() && rule.action != RuleAction::Sanitize {
+            diagnostics.push(RuleDiagnostic::new(
+                i,
+                DiagnosticLevel::Warning,
+                "transforms",
+                "Transforms only apply to sanitize rules".into(),
+                None,
+            ));
+        }
+        if rule.concealed == Some(true)
+            && matches!(rule.action, RuleAction::Sanitize | RuleAction::Redact)
+        {
+            diagnostics.push(RuleDiagnostic::new(
+                i,
+                DiagnosticLevel::Warning,
+                "concealed",
+                "Concealed copies are never rewritten, so this rule has no effect".into(),
+                None,
+            ));
+        }
+        if !rule.enabled {
+            continue;
+        }
+
+        // A scheduled or conditional rule only wins part of the time, so it can't hide
+        // later rules
+        let earlier = rules[..i].iter().enumerate().filter(|(_, r)| {
+            is_valid(r) && r.enabled && r.schedule.is_none() && !has_conditions(r)
+        });
+        for (j, other) in earlier {
+            let same_pair = other.match_source == rule.match_source
+                && same_app(&other.from_app_id, &rule.from_app_id)
+                && same_app(&other.to_app_id, &rule.to_app_id);
+            let diagnostic = if same_pair && other.action == rule.action {
+                RuleDiagnostic::new(
+                    i,
+                    DiagnosticLevel::Warning,
+                    "duplicate",
+                    format!("Duplicate of rule {}", j + 1),
+                    Some(j),
+                )
+            } else if same_pair {
+                RuleDiagnostic::new(
+                    i,
+                    DiagnosticLevel::Warning,
+                    "conflict",
+                    format!(
+                        "Conflicts with rule {}, which applies to the same apps and wins",
+                        j + 1
+                    ),
+                    Some(j),
+                )
+            } else if other.match_source.covers(rule.match_source)
+                && covers(&other.from_app_id, &rule.from_app_id)
+                && covers(&other.to_app_id, &rule.to_app_id)
+            {
+                RuleDiagnostic::new(
+                    i,
+                    DiagnosticLevel::Warning,
+                    "shadowed",
+                    format!("Never applies: rule {} already matches these apps", j + 1),
+                    Some(j),
+                )
+            } else {
+                continue;
+            };
+            diagnostics.push(diagnostic);
+            break;
+        }
+    }
+    diagnostics
+}
+
+pub fn first_error(diagnostics: &[RuleDiagnostic]) -> Option<&RuleDiagnostic> {
+    diagnostics
+        .iter()
+        .find(|d| d.level == DiagnosticLevel::Error)
+}
+
+/// One condition of a rule checked against a paste
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PredicateTrace {
+    pub name: String,
+    /// What the rule asks for; `None` means "any"
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+    pub matched: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RuleTrace {
+    pub index: usize,
+    pub rule: BlockRule,
+    pub predicates: Vec<PredicateTrace>,
+    pub matched: bool,
+    pub winner: bool,
+}
+
+/// Final decision for a paste plus the reasoning behind it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Evaluation {
+    pub source_app_id: Option<String>,
+    pub dest_app_id: String,
+    pub decision: Option<RuleAction>,
+    /// Index of the winning rule
+    pub matched: Option<usize>,
+    /// Id of the winning rule
+    #[serde(default)]
+    pub rule_id: Option<String>,
+    /// Temporary override that let the paste through, if one did
+    #[serde(default)]
+    pub override_id: Option<String>,
+    pub reason: String,
+    pub trace: Vec<RuleTrace>,
+    /// How the trust zones judged the paste, when the policy defines any
+    #[serde(default)]
+    pub zone: Option<ZoneTrace>,
+    /// Age of the copy in minutes, when the stale-paste warning decided
+    #[serde(default)]
+    pub stale_mins: Option<u64>,
+    #[serde(default)]
+    pub findings: Vec<Finding>,
+}
+
+/// The trust zones' verdict on a paste
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ZoneTrace {
+    pub source_zone: Option<String>,
+    pub dest_zone: Option<String>,
+    /// Index of the flow that applied; `None` when the default action did, or nothing
+    pub flow: Option<usize>,
+    pub action: Option<RuleAction>,
+    /// A rule matched first and decided the paste instead
+    pub overridden: bool,
+}
+
+impl ZoneTrace {
+    fn describe(&self) -> String {
+        let zone = |z: &Option<String>| z.clone().unwrap_or_else(|| "no zone".into());
+        let action = self
+            .action
+            .as_ref()
+            .map_or_else(|| "allow".into(), |a| format!("{:?}", a).to_lowercase());
+        let by = match self.flow {
+            Some(i) => format!("flow {}", i + 1),
+            None => "the cross-zone default".into(),
+        };
+        format!(
+            "{} → {} is {} by {}",
+            zone(&self.source_zone),
+            zone(&self.dest_zone),
+            action,
+            by
+        )
+    }
+}
+
+/// Judge a paste by the trust zones its apps are in; `None` when the policy has none
+fn zone_trace(
+    policy: &Policy,
+    source_app_id: Option<&str>,
+    dest_app_id: &str,
+) -> Option<ZoneTrace> {
+    let zones = policy.trust_zones.as_ref()?;
+    let source_zone = source_app_id.and_then(|id| policy.zone_of(id));
+    let dest_zone = policy.zone_of(dest_app_id);
+    let flow = zones
+        .flows
+        .iter()
+        .position(|f| f.covers(source_zone, dest_zone));
+    let action = match flow {
+        Some(i) => Some(zones.flows[i].action.clone()),
+        None if source_zone.is_some() && dest_zone.is_some() && source_zone != dest_zone => {
+            zones.default_action.clone()
+        }
+        None => None,
+    };
+    Some(ZoneTrace {
+        source_zone: source_zone.map(str::to_string),
+        dest_zone: dest_zone.map(str::to_string),
+        flow,
+        action,
+        overridden: false,
+    })
+}
+
+/// What the source app said about the copy, beyond where it came from
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CopyContext {
+    /// Marked as a password or similar secret, e.g. by a password manager
+    #[serde(default)]
+    pub concealed: bool,
+    /// Marked as short-lived, not to be kept in clipboard history
+    #[serde(default)]
+    pub transient: bool,
+    /// Where the content was first copied from, when it has been seen before
+    #[serde(default)]
+    pub origin: Option<Origin>,
+    /// Sensitivity label, carried over from every app the content was copied from
+    #[serde(default)]
+    pub label: Option<String>,
+    /// When the copy was made, in unix seconds
+    #[serde(default)]
+    pub copied_at: Option<u64>,
+    /// When the copy was made, by the monotonic clock; only known to the process that saw it
+    #[serde(skip)]
+    pub copied: Option<Instant>,
+}
+
+impl CopyContext {
+    /// Mark the copy as made just now
+    pub fn stamp(&mut self) {
+        self.copied = Some(Instant::now());
+        self.copied_at = Some(unix_now());
+    }
+
+    /// How long ago the copy was made; falls back to the wall clock for copies seen elsewhere
+    pub fn age(&self) -> Option<Duration> {
+        match (self.copied, self.copied_at) {
+            (Some(copied), _) => Some(copied.elapsed()),
+            (None, Some(at)) => Some(Duration::from_secs(unix_now().saturating_sub(at))),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Check the copy's age against a limit; copies of unknown age never match
+fn age_predicate(
+    name: &str,
+    expected: String,
+    age: Option<Duration>,
+    within: impl Fn(u64) -> bool,
+) -> PredicateTrace {
+    PredicateTrace {
+        name: name.to_string(),
+        expected: Some(expected),
+        actual: Some(age.map_or_else(|| "unknown".into(), |a| format!("{}s", a.as_secs()))),
+        matched: age.is_some_and(|a| within(a.as_secs())),
+    }
+}
+
+fn app_predicate(name: &str, expected: &Option<String>, actual: Option<&str>) -> PredicateTrace {
+    let matched = match expected {
+        None => true,
+        Some(id) => actual.map(|a| a.eq_ignore_ascii_case(id)).unwrap_or(false),
+    };
+    PredicateTrace {
+        name: name.to_string(),
+        expected: expected.clone(),
+        actual: actual.map(str::to_string),
+        matched,
+    }
+}
+
+/// Check the rule's source app against the immediate source, the original one, or both
+fn source_predicate(
+    rule: &BlockRule,
+    source_app_id: Option<&str>,
+    copy: &CopyContext,
+) -> PredicateTrace {
+    let original_app_id = match &copy.origin {
+        Some(origin) => origin.app_id.as_deref(),
+        None => source_app_id,
+    };
+    let immediate = || app_predicate("from_app_id", &rule.from_app_id, source_app_id);
+    let original = || app_predicate("original_app_id", &rule.from_app_id, original_app_id);
+    match rule.match_source {
+        SourceMatch::Immediate => immediate(),
+        SourceMatch::Original => original(),
+        SourceMatch::Either => Some(immediate())
+            .filter(|p| p.matched)
+            .unwrap_or_else(original),
+    }
+}
+
+fn flag_predicate(name: &str, expected: Option<bool>, actual: bool) -> Option<PredicateTrace> {
+    let expected = expected?;
+    Some(PredicateTrace {
+        name: name.to_string(),
+        expected: Some(expected.to_string()),
+        actual: Some(actual.to_string()),
+        matched: expected == actual,
+    })
+}
+
+/// Whether the copy's label is at least `min_label`; labels missing from the policy's
+/// order never match
+fn label_predicate(min_label: &str, label: Option<&str>, policy: &Policy) -> PredicateTrace {
+    let rank = |l: &str| policy.labels.as_ref().and_then(|labels| labels.rank(l));
+    let matched = match (rank(min_label), label.and_then(rank)) {
+        (Some(min), Some(actual)) => actual >= min,
+        _ => false,
+    };
+    PredicateTrace {
+        name: "label".into(),
+        expected: Some(format!("{} or above", min_label)),
+        actual: Some(label.unwrap_or("none").to_string()),
+        matched,
+    }
+}
+
+/// Evaluate every rule for a source→dest pair and record why each one did or didn't match.
+/// The first matching rule wins; rules covered by an active override are skipped. When no
+/// rule matches, the policy's trust zones decide.
+/// `content`, when given, is run through the policy's detectors unless the copy is
+/// concealed.
+pub fn evaluate_paste(
     rules: &[BlockRule],
+    overrides: &[Override],
     source_app_id: Option<&str>,
     dest_app_id: &str,
-) -> Option<BlockRule> {
-    rules
+    copy: &CopyContext,
+    content: Option<&str>,
+    policy: &Policy,
+) -> Evaluation {
+    let findings = content
+        .filter(|_| !copy.concealed)
+        .map(|c| detectors::scan_with(c, &policy.detectors))
+        .unwrap_or_default();
+    let same_app = source_app_id
+        .map(|s| s.eq_ignore_ascii_case(dest_app_id))
+        .unwrap_or(false);
+
+    let now = unix_now();
+    let local = schedule::local_now();
+    let active: Vec<&Override> = overrides.iter().filter(|o| o.is_active(now)).collect();
+    let age = copy.age();
+
+    let mut matched = None;
+    let mut overridden = None;
+    let trace = rules
         .iter()
-        .find(|r| {
-            let from_matches = match &r.from_app_id {
-                None => true,
-                Some(id) => source_app_id
-                    .map(|s| s.eq_ignore_ascii_case(id))
-                    .unwrap_or(false),
-            };
-            let to_matches = match &r.to_app_id {
-                None => true,
-                Some(id) => id.eq_ignore_ascii_case(dest_app_id),
-            };
-            from_matches && to_matches
+        .enumerate()
+        .map(|(index, rule)| {
+            let mut predicates = vec![
+                source_predicate(rule, source_app_id, copy),
+                app_predicate("to_app_id", &rule.to_app_id, Some(dest_app_id)),
+            ];
+            // A rule without any app or condition would match every paste; treat it as inert
+            if !is_valid(rule) {
+                predicates.push(PredicateTrace {
+                    name: "valid".into(),
+                    expected: Some("at least one app or condition".into()),
+                    actual: None,
+                    matched: false,
+                });
+            }
+            predicates.extend(flag_predicate("concealed", rule.concealed, copy.concealed));
+            predicates.extend(flag_predicate("transient", rule.transient, copy.transient));
+            if let Some(min_label) = &rule.min_label {
+                predicates.push(label_predicate(min_label, copy.label.as_deref(), policy));
+            }
+            if let Some(group) = &rule.outside_group {
+                predicates.push(PredicateTrace {
+                    name: "outside_group".into(),
+                    expected: Some(format!("not in {}", group)),
+                    actual: Some(dest_app_id.to_string()),
+                    matched: !policy.in_group(group, dest_app_id),
+                });
+            }
+            if let Some(max) = rule.max_age {
+                let expected = format!("at most {}s", max);
+                predicates.push(age_predicate("max_age", expected, age, |a| a <= max));
+            }
+            if let Some(min) = rule.min_age {
+                let expected = format!("at least {}s", min);
+                predicates.push(age_predicate("min_age", expected, age, |a| a >= min));
+            }
+            if !rule.enabled {
+                predicates.push(PredicateTrace {
+                    name: "enabled".into(),
+                    expected: Some("enabled".into()),
+                    actual: Some("disabled".into()),
+                    matched: false,
+                });
+            }
+            if rule.is_expired(now) {
+                predicates.push(PredicateTrace {
+                    name: "expires_at".into(),
+                    expected: rule.expires_at.map(|t| format!("before {}", t)),
+                    actual: Some(now.to_string()),
+                    matched: false,
+                });
+            }
+            if let Some(sched) = &rule.schedule {
+                predicates.push(PredicateTrace {
+                    name: "schedule".into(),
+                    expected: Some(sched.describe()),
+                    actual: Some(local.format("%a %H:%M").to_string()),
+                    matched: sched.is_active(&local),
+                });
+            }
+            if let Some(o) = active
+                .iter()
+                .find(|o| o.covers(rule, source_app_id, dest_app_id))
+            {
+                let would_match = predicates.iter().all(|p| p.matched);
+                if would_match && overridden.is_none() && matched.is_none() {
+                    overridden = Some(o.id.clone());
+                }
+                predicates.push(PredicateTrace {
+                    name: "override".into(),
+                    expected: Some("no active override".into()),
+                    actual: Some(format!("{} until {}", o.id, o.until)),
+                    matched: false,
+                });
+            }
+            let is_match = !same_app && predicates.iter().all(|p| p.matched);
+            let winner = is_match && matched.is_none();
+            if winner {
+                matched = Some(index);
+            }
+            RuleTrace {
+                index,
+                rule: rule.clone(),
+                predicates,
+                matched: is_match,
+                winner,
+            }
+        })
+        .collect();
+
+    // Rules win over zones, so a rule can carve an exception out of a zone flow
+    let zone = if same_app {
+        None
+    } else {
+        zone_trace(policy, source_app_id, dest_app_id).map(|z| ZoneTrace {
+            overridden: matched.is_some() && z.action.is_some(),
+            ..z
         })
+    };
+    let zone_action = zone
+        .as_ref()
+        .filter(|z| !z.overridden)
+        .and_then(|z| z.action.clone());
+
+    let decision = matched
+        .map(|i| rules[i].action.clone())
+        .or(zone_action.clone());
+    // Nothing else had a say and nothing let it through: warn about old content
+    let stale_mins = if same_app || decision.is_some() || overridden.is_some() {
+        None
+    } else {
+        stale_minutes(policy, dest_app_id, age)
+    };
+    let decision = decision.or(stale_mins.map(|_| RuleAction::Notify));
+    let reason = match (same_app, matched) {
+        (true, _) => "Same-app paste is always allowed".to_string(),
+        (false, Some(i)) => {
+            let rule = match &rules[i].name {
+                Some(name) => format!("Rule {} (\"{}\") matched first", i + 1, name),
+                None => format!("Rule {} matched first", i + 1),
+            };
+            match zone.as_ref().filter(|z| z.overridden) {
+                Some(z) => format!("{}, ahead of the trust zones ({})", rule, z.describe()),
+                None => rule,
+            }
+        }
+        (false, None) if zone_action.is_some() => {
+            format!(
+                "Trust zones: {}",
+                zone.as_ref().map(ZoneTrace::describe).unwrap_or_default()
+            )
+        }
+        (false, None) if overridden.is_some() => "Allowed by a temporary override".to_string(),
+        (false, None) => match stale_mins {
+            Some(mins) => format!("Copied {} minutes ago", mins),
+            None => "No rule matches this pair".to_string(),
+        },
+    };
+
+    Evaluation {
+        source_app_id: source_app_id.map(str::to_string),
+        dest_app_id: dest_app_id.to_string(),
+        decision,
+        matched,
+        rule_id: matched.map(|i| rules[i].id.clone()),
+        // Overrides only lift user rules; the zones are policy and still decide
+        override_id: if matched.is_none() && zone_action.is_none() {
+            overridden
+        } else {
+            None
+        },
+        reason,
+        trace,
+        zone,
+        stale_mins,
+        findings,
+    }
+}
+
+/// Minutes since the copy when the policy's stale-paste warning applies to this paste
+fn stale_minutes(policy: &Policy, dest_app_id: &str, age: Option<Duration>) -> Option<u64> {
+    let stale = policy.stale_paste.as_ref()?;
+    let into_group = stale.groups.is_empty()
+        || stale
+            .groups
+            .iter()
+            .any(|group| policy.in_group(group, dest_app_id));
+    let mins = age?.as_secs() / 60;
+    (into_group && mins >= stale.after_mins).then_some(mins)
+}
+
+/// Rules that could fire right now: enabled, valid, not expired or snoozed, and inside
+/// their schedule
+pub fn active_rules(rules: &[BlockRule], overrides: &[Override]) -> Vec<BlockRule> {
+    let now = unix_now();
+    let local = schedule::local_now();
+    rules
+        .iter()
+        .filter(|r| is_valid(r) && r.enabled && !r.is_expired(now))
+        .filter(|r| r.schedule.as_ref().is_none_or(|s| s.is_active(&local)))
+        .filter(|r| !overrides.iter().any(|o| o.is_active(now) && o.snoozes(r)))
         .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::overrides::OverrideTarget;
+    use crate::policy::{TrustZones, Zone, ZoneFlow};
+    use crate::schedule::TimeWindow;
+
+    fn zone(name: &str, apps: &[&str]) -> Zone {
+        Zone {
+            name: name.into(),
+            apps: apps.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    fn flow(from: &str, to: &str, action: RuleAction) -> ZoneFlow {
+        ZoneFlow {
+            from: from.into(),
+            to: to.into(),
+            action,
+        }
+    }
+
+    /// Work apps (one listed through a group) and a personal zone that shares an app
+    fn zoned(flows: Vec<ZoneFlow>, default_action: Option<RuleAction>) -> Policy {
+        Policy {
+            app_groups: [("office".to_string(), vec!["com.acme.mail".to_string()])].into(),
+            trust_zones: Some(TrustZones {
+                zones: vec![
+                    zone("work", &["com.acme.crm", "@office", "com.shared.notes"]),
+                    zone("personal", &["com.chat.app", "com.shared.notes"]),
+                ],
+                flows,
+                default_action,
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn paste(
+        rules: &[BlockRule],
+        overrides: &[Override],
+        from: &str,
+        to: &str,
+        policy: &Policy,
+    ) -> Evaluation {
+        evaluate_paste(
+            rules,
+            overrides,
+            Some(from),
+            to,
+            &CopyContext::default(),
+            None,
+            policy,
+        )
+    }
+
+    #[test]
+    fn zone_of_resolves_ids_groups_and_first_zone() {
+        let policy = zoned(Vec::new(), None);
+        assert_eq!(policy.zone_of("COM.ACME.CRM"), Some("work"));
+        assert_eq!(policy.zone_of("com.acme.mail"), Some("work"));
+        assert_eq!(policy.zone_of("com.chat.app"), Some("personal"));
+        assert_eq!(policy.zone_of("com.shared.notes"), Some("work"));
+        assert_eq!(policy.zone_of("com.unknown"), None);
+    }
+
+    #[test]
+    fn first_covering_flow_decides() {
+        let policy = zoned(
+            vec![
+                flow("work", "personal", RuleAction::Block),
+                flow("work", "*", RuleAction::Notify),
+            ],
+            None,
+        );
+        let eval = paste(&[], &[], "com.acme.crm", "com.chat.app", &policy);
+        assert_eq!(eval.decision, Some(RuleAction::Block));
+        assert_eq!(eval.zone.as_ref().and_then(|z| z.flow), Some(0));
+        assert!(eval.reason.starts_with("Trust zones: work → personal"));
+    }
+
+    #[test]
+    fn wildcard_flow_covers_apps_outside_every_zone() {
+        let policy = zoned(vec![flow("work", "*", RuleAction::Confirm)], None);
+        let eval = paste(&[], &[], "com.acme.mail", "com.unknown", &policy);
+        assert_eq!(eval.decision, Some(RuleAction::Confirm));
+        let zone = eval.zone.unwrap();
+        assert_eq!(zone.dest_zone, None);
+        assert_eq!(zone.flow, Some(0));
+    }
+
+    #[test]
+    fn default_action_only_applies_between_two_zones() {
+        let policy = zoned(Vec::new(), Some(RuleAction::Block));
+        let across = paste(&[], &[], "com.chat.app", "com.acme.crm", &policy);
+        assert_eq!(across.decision, Some(RuleAction::Block));
+        assert_eq!(across.zone.as_ref().and_then(|z| z.flow), None);
+
+        let within = paste(&[], &[], "com.acme.crm", "com.acme.mail", &policy);
+        assert_eq!(within.decision, None);
+        let outside = paste(&[], &[], "com.chat.app", "com.unknown", &policy);
+        assert_eq!(outside.decision, None);
+    }
+
+    #[test]
+    fn rules_decide_ahead_of_zones() {
+        let policy = zoned(vec![flow("work", "personal", RuleAction::Block)], None);
+        let rules = vec![BlockRule::new(
+            Some("com.acme.crm".into()),
+            Some("com.chat.app".into()),
+            RuleAction::Allow,
+        )];
+        let eval = paste(&rules, &[], "com.acme.crm", "com.chat.app", &policy);
+        assert_eq!(eval.decision, Some(RuleAction::Allow));
+        assert_eq!(eval.matched, Some(0));
+        assert!(eval.zone.unwrap().overridden);
+    }
+
+    #[test]
+    fn override_does_not_lift_a_zone_flow() {
+        let policy = zoned(vec![flow("work", "personal", RuleAction::Block)], None);
+        let rules = vec![BlockRule::new(
+            Some("com.acme.crm".into()),
+            Some("com.chat.app".into()),
+            RuleAction::Notify,
+        )];
+        let allow = Override::new(
+            OverrideTarget::AllowPair {
+                from_app_id: Some("com.acme.crm".into()),
+                from_app_name: None,
+                to_app_id: "com.chat.app".into(),
+                to_app_name: None,
+            },
+            unix_now() + 600,
+        );
+        let overrides = [allow];
+        let eval = paste(&rules, &overrides, "com.acme.crm", "com.chat.app", &policy);
+        assert_eq!(eval.matched, None);
+        assert_eq!(eval.decision, Some(RuleAction::Block));
+        assert_eq!(eval.override_id, None);
+        assert!(eval.reason.starts_with("Trust zones"));
+
+        // Without a zone flow the override lets the pair through
+        let eval = paste(
+            &rules,
+            &overrides,
+            "com.acme.crm",
+            "com.chat.app",
+            &Policy::default(),
+        );
+        assert_eq!(eval.decision, None);
+        assert_eq!(eval.override_id, Some(overrides[0].id.clone()));
+    }
+
+    fn rule(from: Option<&str>, to: Option<&str>, action: RuleAction) -> BlockRule {
+        BlockRule::new(from.map(str::to_string), to.map(str::to_string), action)
+    }
+
+    #[test]
+    fn first_matching_rule_wins_and_every_rule_is_traced() {
+        let rules = vec![
+            BlockRule {
+                enabled: false,
+                ..rule(
+                    Some("com.acme.crm"),
+                    Some("com.chat.app"),
+                    RuleAction::Allow,
+                )
+            },
+            rule(
+                Some("com.acme.crm"),
+                Some("com.chat.app"),
+                RuleAction::Block,
+            ),
+            rule(Some("com.acme.crm"), None, RuleAction::Notify),
+        ];
+        let eval = paste(
+            &rules,
+            &[],
+            "com.acme.crm",
+            "com.chat.app",
+            &Policy::default(),
+        );
+        assert_eq!(eval.decision, Some(RuleAction::Block));
+        assert_eq!(eval.matched, Some(1));
+        assert_eq!(eval.reason, "Rule 2 matched first");
+
+        let outcome: Vec<(bool, bool)> = eval.trace.iter().map(|t| (t.matched, t.winner)).collect();
+        assert_eq!(outcome, [(false, false), (true, true), (true, false)]);
+        let disabled = &eval.trace[0].predicates;
+        assert!(disabled.iter().any(|p| p.name == "enabled" && !p.matched));
+    }
+
+    #[test]
+    fn trace_records_expected_and_actual_apps() {
+        let rules = vec![rule(
+            Some("com.acme.crm"),
+            Some("com.chat.app"),
+            RuleAction::Block,
+        )];
+        let eval = paste(&rules, &[], "com.other", "COM.CHAT.APP", &Policy::default());
+        assert_eq!(eval.decision, None);
+        assert_eq!(eval.reason, "No rule matches this pair");
+
+        let predicates = &eval.trace[0].predicates;
+        assert_eq!(predicates[0].name, "from_app_id");
+        assert_eq!(predicates[0].expected.as_deref(), Some("com.acme.crm"));
+        assert_eq!(predicates[0].actual.as_deref(), Some("com.other"));
+        assert!(!predicates[0].matched);
+        // App ids compare without case
+        assert_eq!(predicates[1].name, "to_app_id");
+        assert!(predicates[1].matched);
+    }
+
+    #[test]
+    fn each_action_is_the_decision_of_its_rule() {
+        for action in [RuleAction::Notify, RuleAction::Block, RuleAction::Allow] {
+            let rules = vec![
+                rule(None, Some("com.chat.app"), action.clone()),
+                rule(None, Some("com.chat.app"), RuleAction::Block),
+            ];
+            let eval = paste(
+                &rules,
+                &[],
+                "com.acme.crm",
+                "com.chat.app",
+                &Policy::default(),
+            );
+            assert_eq!(eval.decision, Some(action));
+            assert_eq!(eval.matched, Some(0));
+            assert_eq!(eval.rule_id, Some(rules[0].id.clone()));
+        }
+    }
+
+    #[test]
+    fn named_rule_is_quoted_in_the_reason() {
+        let rules = vec![BlockRule {
+            name: Some("No chat".into()),
+            ..rule(None, Some("com.chat.app"), RuleAction::Notify)
+        }];
+        let eval = paste(
+            &rules,
+            &[],
+            "com.acme.crm",
+            "com.chat.app",
+            &Policy::default(),
+        );
+        assert_eq!(eval.reason, "Rule 1 (\"No chat\") matched first");
+    }
+
+    #[test]
+    fn same_app_and_invalid_rules_never_match() {
+        let rules = vec![rule(None, None, RuleAction::Block)];
+        let eval = paste(
+            &rules,
+            &[],
+            "com.acme.crm",
+            "com.chat.app",
+            &Policy::default(),
+        );
+        assert_eq!(eval.matched, None);
+        let predicates = &eval.trace[0].predicates;
+        assert!(predicates.iter().any(|p| p.name == "valid" && !p.matched));
+
+        let rules = vec![rule(None, Some("com.acme.crm"), RuleAction::Block)];
+        let eval = paste(
+            &rules,
+            &[],
+            "com.acme.crm",
+            "com.acme.crm",
+            &Policy::default(),
+        );
+        assert_eq!(eval.decision, None);
+        assert!(!eval.trace[0].matched);
+        assert_eq!(eval.reason, "Same-app paste is always allowed");
+    }
+
+    /// `(index, code, related)` for each diagnostic
+    fn lints(rules: &[BlockRule]) -> Vec<(usize, String, Option<usize>)> {
+        lint(rules)
+            .into_iter()
+            .map(|d| (d.index, d.code, d.related))
+            .collect()
+    }
+
+    #[test]
+    fn rule_without_apps_or_conditions_is_an_error() {
+        let rules = vec![
+            rule(None, Some("com.chat.app"), RuleAction::Notify),
+            rule(None, None, RuleAction::Block),
+        ];
+        let diagnostics = lint(&rules);
+        let error = first_error(&diagnostics).unwrap();
+        assert_eq!((error.index, error.code.as_str()), (1, "invalid"));
+
+        // A condition alone is enough
+        let concealed = BlockRule {
+            concealed: Some(true),
+            ..rule(None, None, RuleAction::Block)
+        };
+        assert!(lint(&[concealed]).is_empty());
+    }
+
+    #[test]
+    fn bad_schedule_is_an_error() {
+        let window = |start: &str, end: &str| TimeWindow {
+            days: Vec::new(),
+            start: start.into(),
+            end: end.into(),
+        };
+        for windows in [
+            vec![],
+            vec![window("9am", "17:00")],
+            vec![window("09:00", "09:00")],
+        ] {
+            let scheduled = BlockRule {
+                schedule: Some(Schedule { windows }),
+                ..rule(None, Some("com.chat.app"), RuleAction::Block)
+            };
+            let diagnostics = lint(&[scheduled]);
+            assert_eq!(first_error(&diagnostics).unwrap().code, "schedule");
+        }
+    }
+
+    #[test]
+    fn warnings_are_not_errors() {
+        let rules = vec![
+            BlockRule {
+                transforms: vec![Transform::StripAnsi],
+                ..rule(None, Some("com.chat.app"), RuleAction::Block)
+            },
+            BlockRule {
+                concealed: Some(true),
+                ..rule(None, Some("com.mail"), RuleAction::Redact)
+            },
+        ];
+        let diagnostics = lint(&rules);
+        let codes: Vec<&str> = diagnostics.iter().map(|d| d.code.as_str()).collect();
+        assert_eq!(codes, ["transforms", "concealed"]);
+        assert!(first_error(&diagnostics).is_none());
+    }
+
+    #[test]
+    fn later_rules_for_the_same_apps_are_flagged() {
+        let rules = vec![
+            rule(
+                Some("com.acme.crm"),
+                Some("com.chat.app"),
+                RuleAction::Block,
+            ),
+            rule(
+                Some("COM.ACME.CRM"),
+                Some("com.chat.app"),
+                RuleAction::Block,
+            ),
+            rule(
+                Some("com.acme.crm"),
+                Some("com.chat.app"),
+                RuleAction::Allow,
+            ),
+            rule(None, Some("com.mail"), RuleAction::Notify),
+            rule(Some("com.acme.crm"), Some("com.mail"), RuleAction::Block),
+        ];
+        assert_eq!(
+            lints(&rules),
+            [
+                (1, "duplicate".to_string(), Some(0)),
+                (2, "conflict".to_string(), Some(0)),
+                (4, "shadowed".to_string(), Some(3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn disabled_or_conditional_rules_hide_nothing() {
+        let pair = || {
+            rule(
+                Some("com.acme.crm"),
+                Some("com.chat.app"),
+                RuleAction::Block,
+            )
+        };
+        let rules = vec![
+            BlockRule {
+                enabled: false,
+                ..pair()
+            },
+            BlockRule {
+                transient: Some(true),
+                ..pair()
+            },
+            pair(),
+            BlockRule {
+                enabled: false,
+                ..pair()
+            },
+        ];
+        assert!(lints(&rules).is_empty());
+    }
+
+    #[test]
+    fn older_documents_migrate_to_the_current_schema() {
+        let rule = json!({
+            "from_app_id": "com.acme.crm",
+            "from_app_name": null,
+            "to_app_id": "com.chat.app",
+            "to_app_name": "Chat",
+            "action": "block",
+        });
+        let v0 = json!([rule, rule]);
+        let (rules, version) = parse_and_migrate(&v0.to_string()).unwrap();
+        assert_eq!(version, 0);
+        assert_eq!(rules.len(), 2);
+        assert!(rules.iter().all(|r| r.id.starts_with('r') && r.enabled));
+        assert_ne!(rules[0].id, rules[1].id);
+        assert_eq!(rules[0].to_app_name.as_deref(), Some("Chat"));
+        assert_eq!(rules[0].action, RuleAction::Block);
+
+        let mut kept = rule.clone();
+        kept["id"] = json!("keep-me");
+        let v1 = json!({ "version": 1, "rules": [kept, rule] });
+        let (rules, version) = parse_and_migrate(&v1.to_string()).unwrap();
+        assert_eq!(version, 1);
+        assert_eq!(rules[0].id, "keep-me");
+        assert!(!rules[1].id.is_empty());
+
+        // Migrating again is a no-op, and each step bumps the version
+        let v2 = migrate_v1_to_v2(migrate_v0_to_v1(v0).unwrap()).unwrap();
+        assert_eq!(schema_version(&v2), Ok(SCHEMA_VERSION));
+        let (again, version) = parse_and_migrate(&v2.to_string()).unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+        let ids: Vec<Value> = again.iter().map(|r| json!(r.id)).collect();
+        assert_eq!(
+            ids,
+            [v2["rules"][0]["id"].clone(), v2["rules"][1]["id"].clone()]
+        );
+    }
+
+    #[test]
+    fn unknown_schema_versions_are_rejected() {
+        let newer = json!({ "version": SCHEMA_VERSION + 1, "rules": [] });
+        assert!(parse_and_migrate(&newer.to_string())
+            .unwrap_err()
+            .contains("newer"));
+        assert!(parse_and_migrate("{\"rules\": []}").is_err());
+        assert!(parse_and_migrate("\"rules\"").is_err());
+        let v1 = json!({ "version": 1, "rules": [1] });
+        assert_eq!(
+            parse_and_migrate(&v1.to_string()).unwrap_err(),
+            "rule is not an object"
+        );
+    }
 }
@@ -0,0 +1,529 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::policy::{self, Policy};
+use crate::rules::{self, BlockRule, RuleAction, SourceMatch};
+use crate::sanitize::Transform;
+use crate::schedule::Schedule;
+
+const HEADER: &str = "\
+# Clipboard Guard rules
+#
+# from / to: an app id, or \"@name\" for every app listed under groups.name.
+#            Leave out for any app.
+# match_source: which app \"from\" is checked against when content is copied
+#            again: \"immediate\" (default, the app it was last copied from),
+#            \"original\" (the app it was first copied from) or \"either\"
+# action:    \"notify\", \"block\", \"confirm\", \"sanitize\", \"redact\" or
+#            \"allow\"
+# enabled:   false keeps a rule without applying it
+# schedule:  windows with days (mon … sun, empty for every day) and a local
+#            start / end time (\"HH:MM\"); leave out to apply all the time
+# transforms: for sanitize rules, any of strip_hidden, strip_ansi,
+#            ascii_punctuation, trim_trailing_newline, collapse_lines
+# concealed: true for copies a password manager marked as secret, false for
+#            every other copy; leave out for both
+# transient: the same, for copies marked not to be kept in clipboard history
+# min_label: only copies labelled at least this sensitive, in the order the
+#            managed policy defines
+# outside_group: only pastes into apps outside this managed policy app group
+# max_age / min_age: only copies made at most / at least this many seconds
+#            before the paste
+";
+
+/// An app id and display name, either of which may be unset
+type AppRef = (Option<String>, Option<String>);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Toml,
+    Yaml,
+}
+
+impl Format {
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("toml") => Ok(Format::Toml),
+            Some("yaml" | "yml") => Ok(Format::Yaml),
+            _ => Err(format!(
+                "{}: expected a .toml, .yaml or .yml file",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// Shareable rule file. Human-edited, so it uses short field names and app groups.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RuleSet {
+    /// Named sets of app ids, referenced from rules as `"@name"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub groups: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub rules: Vec<SharedRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SharedRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_name: Option<String>,
+    #[serde(default, skip_serializing_if = "SourceMatch::is_immediate")]
+    pub match_source: SourceMatch,
+    pub action: RuleAction,
+    #[serde(default = "default_enabled", skip_serializing_if = "is_enabled")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<Schedule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<Transform>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concealed: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transient: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outside_group: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_age: Option<u64>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn is_enabled(enabled: &bool) -> bool {
+    *enabled
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Append imported rules that aren't already present
+    Merge,
+    /// Swap the user's rules for the imported ones
+    Replace,
+}
+
+/// What an import would do, shown to the user before it's applied
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub added: Vec<BlockRule>,
+    pub removed: Vec<BlockRule>,
+    pub unchanged: usize,
+    /// The user's rule list after the import
+    pub rules: Vec<BlockRule>,
+    /// Why applying the import would be refused, if it would
+    pub error: Option<String>,
+}
+
+impl RuleSet {
+    /// Rules expanded from a group are written back as one `"@group"` rule
+    pub fn from_rules(rules: &[BlockRule]) -> Self {
+        let mut members: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for rule in rules {
+            let refs = [
+                (&rule.from_group, &rule.from_app_id),
+                (&rule.to_group, &rule.to_app_id),
+            ];
+            for (group, app) in refs {
+                if let (Some(group), Some(app)) = (group, app) {
+                    let apps = members.entry(group.clone()).or_default();
+                    if !apps.contains(app) {
+                        apps.push(app.clone());
+                    }
+                }
+            }
+        }
+        let mut set = RuleSet::default();
+        let mut rest = rules;
+        while let Some(first) = rest.first() {
+            let run = rest.iter().take_while(|r| same_shape(first, r)).count();
+            // Neighbouring rules with the same settings may come from different group rules
+            let collapsed = (1..=run)
+                .rev()
+                .find_map(|len| Some((len, set.collapse(&rest[..len], &members)?)));
+            let (taken, shared) = collapsed.unwrap_or_else(|| {
+                (
+                    1,
+                    shared_rule(first, app_ref(first, true), app_ref(first, false)),
+                )
+            });
+            set.rules.push(shared);
+            rest = &rest[taken..];
+        }
+        set
+    }
+
+    /// One rule for a run that expands a group reference over every app of the group,
+    /// registering the groups it uses. None when the run isn't such an expansion.
+    fn collapse(
+        &mut self,
+        run: &[BlockRule],
+        members: &BTreeMap<String, Vec<String>>,
+    ) -> Option<SharedRule> {
+        let first = &run[0];
+        if first.from_group.is_none() && first.to_group.is_none() {
+            return None;
+        }
+        let from = distinct(run.iter().map(|r| &r.from_app_id));
+        let to = distinct(run.iter().map(|r| &r.to_app_id));
+        let expands = run.len() == from.len() * to.len()
+            && run.iter().enumerate().all(|(i, r)| {
+                r.from_app_id == from[i / to.len()] && r.to_app_id == to[i % to.len()]
+            });
+        if !expands {
+            return None;
+        }
+        let mut groups = Vec::new();
+        for (group, apps) in [(&first.from_group, &from), (&first.to_group, &to)] {
+            match group {
+                Some(name) => {
+                    let ids = apps.iter().cloned().collect::<Option<Vec<String>>>()?;
+                    if members.get(name) != Some(&ids) {
+                        return None;
+                    }
+                    groups.push((name.clone(), ids));
+                }
+                None if apps.len() > 1 => return None,
+                None => {}
+            }
+        }
+        self.groups.extend(groups);
+        let from = match &first.from_group {
+            Some(name) => (Some(format!("@{}", name)), None),
+            None => app_ref(first, true),
+        };
+        let to = match &first.to_group {
+            Some(name) => (Some(format!("@{}", name)), None),
+            None => app_ref(first, false),
+        };
+        Some(shared_rule(first, from, to))
+    }
+
+    /// Expand group references into one `BlockRule` per app pair
+    pub fn to_rules(&self) -> Result<Vec<BlockRule>, String> {
+        let mut expanded = Vec::new();
+        for (i, rule) in self.rules.iter().enumerate() {
+            let label = rule
+                .name
+                .as_ref()
+                .map_or_else(|| format!("Rule {}", i + 1), |n| format!("Rule \"{}\"", n));
+            let from = self.resolve(&rule.from, &rule.from_name, &label)?;
+            let to = self.resolve(&rule.to, &rule.to_name, &label)?;
+            let from_group = group_name(&rule.from).map(str::to_string);
+            let to_group = group_name(&rule.to).map(str::to_string);
+            for (from_app_id, from_app_name) in &from {
+                for (to_app_id, to_app_name) in &to {
+                    expanded.push(BlockRule {
+                        name: rule.name.clone(),
+                        description: rule.description.clone(),
+                        from_app_name: from_app_name.clone(),
+                        to_app_name: to_app_name.clone(),
+                        from_group: from_group.clone(),
+                        to_group: to_group.clone(),
+                        match_source: rule.match_source,
+                        enabled: rule.enabled,
+                        tags: rule.tags.clone(),
+                        schedule: rule.schedule.clone(),
+                        transforms: rule.transforms.clone(),
+                        concealed: rule.concealed,
+                        transient: rule.transient,
+                        min_label: rule.min_label.clone(),
+                        outside_group: rule.outside_group.clone(),
+                        max_age: rule.max_age,
+                        min_age: rule.min_age,
+                        ..BlockRule::new(
+                            from_app_id.clone(),
+                            to_app_id.clone(),
+                            rule.action.clone(),
+                        )
+                    });
+                }
+            }
+        }
+        Ok(expanded)
+    }
+
+    /// The apps a `from` / `to` reference stands for
+    fn resolve(
+        &self,
+        app: &Option<String>,
+        name: &Option<String>,
+        label: &str,
+    ) -> Result<Vec<AppRef>, String> {
+        let Some(group) = group_name(app) else {
+            return Ok(vec![(app.clone(), name.clone())]);
+        };
+        match self.groups.get(group) {
+            Some(ids) if !ids.is_empty() => {
+                Ok(ids.iter().map(|id| (Some(id.clone()), None)).collect())
+            }
+            Some(_) => Err(format!("{}: group \"{}\" is empty", label, group)),
+            None => Err(format!("{}: unknown group \"{}\"", label, group)),
+        }
+    }
+
+    pub fn parse(text: &str, format: Format) -> Result<Self, String> {
+        match format {
+            Format::Toml => toml::from_str(text).map_err(|e| e.to_string()),
+            Format::Yaml => serde_yaml::from_str(text).map_err(|e| e.to_string()),
+        }
+    }
+
+    pub fn render(&self, format: Format) -> Result<String, String> {
+        let body = match format {
+            Format::Toml => toml::to_string_pretty(self).map_err(|e| e.to_string())?,
+            Format::Yaml => serde_yaml::to_string(self).map_err(|e| e.to_string())?,
+        };
+        Ok(format!("{}\n{}", HEADER, body))
+    }
+}
+
+/// The group a `from` / `to` reference names, if it is `"@group"`
+fn group_name(app: &Option<String>) -> Option<&str> {
+    app.as_deref().and_then(|a| a.strip_prefix('@'))
+}
+
+/// The concrete source (`from`) or destination app of a rule
+fn app_ref(rule: &BlockRule, from: bool) -> AppRef {
+    if from {
+        (rule.from_app_id.clone(), rule.from_app_name.clone())
+    } else {
+        (rule.to_app_id.clone(), rule.to_app_name.clone())
+    }
+}
+
+/// Equal apart from ids, timestamps and the apps themselves, so possibly one group expansion
+fn same_shape(a: &BlockRule, b: &BlockRule) -> bool {
+    let strip = |r: &BlockRule| BlockRule {
+        from_app_id: None,
+        from_app_name: None,
+        to_app_id: None,
+        to_app_name: None,
+        ..r.clone()
+    };
+    a.from_group == b.from_group && a.to_group == b.to_group && strip(a).same_content(&strip(b))
+}
+
+/// Each value once, in first-seen order
+fn distinct<'a>(values: impl Iterator<Item = &'a Option<String>>) -> Vec<Option<String>> {
+    let mut seen: Vec<Option<String>> = Vec::new();
+    for value in values {
+        if !seen.contains(value) {
+            seen.push(value.clone());
+        }
+    }
+    seen
+}
+
+fn shared_rule(rule: &BlockRule, from: AppRef, to: AppRef) -> SharedRule {
+    SharedRule {
+        name: rule.name.clone(),
+        description: rule.description.clone(),
+        from: from.0,
+        from_name: from.1,
+        to: to.0,
+        to_name: to.1,
+        match_source: rule.match_source,
+        action: rule.action.clone(),
+        enabled: rule.enabled,
+        tags: rule.tags.clone(),
+        schedule: rule.schedule.clone(),
+        transforms: rule.transforms.clone(),
+        concealed: rule.concealed,
+        transient: rule.transient,
+        min_label: rule.min_label.clone(),
+        outside_group: rule.outside_group.clone(),
+        max_age: rule.max_age,
+        min_age: rule.min_age,
+    }
+}
+
+/// Read a TOML/YAML rule file into expanded `BlockRule`s
+pub fn read_file(path: &Path) -> Result<Vec<BlockRule>, String> {
+    let format = Format::from_path(path)?;
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    RuleSet::parse(&text, format)
+        .and_then(|set| set.to_rules())
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Write the user's rules to `path`, in the format its extension names
+pub fn write_file(path: &Path, rules: &[BlockRule]) -> Result<(), String> {
+    let text = RuleSet::from_rules(rules).render(Format::from_path(path)?)?;
+    fs::write(path, text).map_err(|e| format!("Could not write {}: {}", path.display(), e))
+}
+
+/// Imported rules have no id yet, so compare settings only
+fn contains(rules: &[BlockRule], rule: &BlockRule) -> bool {
+    rules.iter().any(|r| r.same_content(rule))
+}
+
+/// Work out the user's rules after importing `imported` into the current `rules`.
+/// Fails when the policy doesn't let the user edit rules at all.
+pub fn plan(
+    managed_policy: &Policy,
+    rules: &[BlockRule],
+    imported: Vec<BlockRule>,
+    mode: ImportMode,
+) -> Result<ImportPreview, String> {
+    let current = policy::user_rules(rules);
+    let mut result = match mode {
+        ImportMode::Merge => current.clone(),
+        ImportMode::Replace => Vec::new(),
+    };
+    for rule in imported {
+        if !contains(&result, &rule) {
+            result.push(rule);
+        }
+    }
+    // A rule the user already has keeps its id and timestamps
+    let mut unmatched: Vec<&BlockRule> = current.iter().collect();
+    for rule in result.iter_mut().filter(|r| r.id.is_empty()) {
+        if let Some(i) = unmatched.iter().position(|c| c.same_content(rule)) {
+            let existing = unmatched.remove(i);
+            rule.id = existing.id.clone();
+            rule.created_at = existing.created_at;
+            rule.modified_at = existing.modified_at;
+        }
+    }
+    let result = policy::apply_user_edit(
+        managed_policy,
+        rules,
+        &policy::merge(managed_policy, result),
+    )?;
+
+    let diagnostics = rules::lint(&policy::merge(managed_policy, result.clone()));
+    Ok(ImportPreview {
+        added: result
+            .iter()
+            .filter(|r| !contains(&current, r))
+            .cloned()
+            .collect(),
+        removed: current
+            .iter()
+            .filter(|r| !contains(&result, r))
+            .cloned()
+            .collect(),
+        unchanged: current.iter().filter(|r| contains(&result, r)).count(),
+        error: rules::first_error(&diagnostics)
+            .map(|e| format!("Rule {}: {}", e.index + 1, e.message)),
+        rules: result,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHARED: &str = r#"
+[groups]
+browsers = ["com.a.browser", "com.b.browser"]
+chat = ["com.chat.one", "com.chat.two"]
+
+[[rules]]
+from = "com.acme.crm"
+to = "@browsers"
+action = "block"
+
+[[rules]]
+from = "@chat"
+to = "@browsers"
+action = "notify"
+
+[[rules]]
+from = "com.acme.crm"
+to = "com.c.browser"
+action = "block"
+"#;
+
+    #[test]
+    fn groups_survive_export_and_import() {
+        let set = RuleSet::parse(SHARED, Format::Toml).unwrap();
+        let rules = set.to_rules().unwrap();
+        assert_eq!(rules.len(), 2 + 4 + 1);
+
+        let exported = RuleSet::from_rules(&rules);
+        assert_eq!(exported, set);
+        let text = exported.render(Format::Yaml).unwrap();
+        let reread = RuleSet::parse(&text, Format::Yaml).unwrap();
+        assert_eq!(reread.to_rules().unwrap(), rules);
+    }
+
+    #[test]
+    fn edited_group_rules_are_written_out_one_by_one() {
+        let mut rules = RuleSet::parse(SHARED, Format::Toml)
+            .unwrap()
+            .to_rules()
+            .unwrap();
+        rules[1].action = RuleAction::Notify;
+
+        let exported = RuleSet::from_rules(&rules);
+        let tos: Vec<_> = exported.rules.iter().map(|r| r.to.as_deref()).collect();
+        assert_eq!(
+            tos,
+            [
+                Some("com.a.browser"),
+                Some("com.b.browser"),
+                Some("@browsers"),
+                Some("com.c.browser"),
+            ]
+        );
+        let reread = exported.to_rules().unwrap();
+        assert!(reread.iter().zip(&rules).all(|(a, b)| a.same_content(b)));
+    }
+
+    #[test]
+    fn replace_keeps_ids_of_unchanged_rules() {
+        let mut existing = BlockRule::new(
+            Some("com.acme.crm".into()),
+            Some("com.a.browser".into()),
+            RuleAction::Block,
+        );
+        existing.id = "kept".into();
+        existing.created_at = Some(100);
+        let dropped = BlockRule {
+            id: "dropped".into(),
+            ..BlockRule::new(None, Some("com.chat.one".into()), RuleAction::Notify)
+        };
+        let current = [existing.clone(), dropped.clone()];
+        let imported = RuleSet::parse(SHARED, Format::Toml)
+            .unwrap()
+            .to_rules()
+            .unwrap();
+
+        let preview = plan(&Policy::default(), &current, imported, ImportMode::Replace).unwrap();
+        assert_eq!(preview.rules[0].id, "kept");
+        assert_eq!(preview.rules[0].created_at, Some(100));
+        assert_eq!(preview.rules[0].to_group.as_deref(), Some("browsers"));
+        assert!(preview.rules[1..].iter().all(|r| r.id.is_empty()));
+        assert_eq!(preview.unchanged, 1);
+        assert_eq!(preview.removed, [dropped]);
+    }
+}
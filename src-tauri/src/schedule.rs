@@ -0,0 +1,198 @@
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Day {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Day {
+    fn weekday(self) -> Weekday {
+        match self {
+            Day::Mon => Weekday::Mon,
+            Day::Tue => Weekday::Tue,
+            Day::Wed => Weekday::Wed,
+            Day::Thu => Weekday::Thu,
+            Day::Fri => Weekday::Fri,
+            Day::Sat => Weekday::Sat,
+            Day::Sun => Weekday::Sun,
+        }
+    }
+}
+
+/// Days plus a local `"HH:MM"` range. An `end` before `start` runs past midnight and
+/// belongs to the day it starts on.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimeWindow {
+    /// Empty means every day
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<Day>,
+    pub start: String,
+    pub end: String,
+}
+
+/// When a rule applies, in local time. Active if any window contains the current time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Schedule {
+    pub windows: Vec<TimeWindow>,
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| format!("\"{}\" is not a HH:MM time", value))
+}
+
+impl TimeWindow {
+    fn on(&self, day: Weekday) -> bool {
+        self.days.is_empty() || self.days.iter().any(|d| d.weekday() == day)
+    }
+
+    fn contains(&self, now: &NaiveDateTime) -> bool {
+        let (Ok(start), Ok(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+        let time = now.time();
+        if start <= end {
+            self.on(now.weekday()) && start <= time && time < end
+        } else {
+            // Overnight: the evening part today, or the early-morning tail of yesterday's window
+            (self.on(now.weekday()) && time >= start)
+                || (self.on(now.weekday().pred()) && time < end)
+        }
+    }
+}
+
+impl Schedule {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.windows.is_empty() {
+            return Err("Schedule has no time windows".into());
+        }
+        for w in &self.windows {
+            let start = parse_time(&w.start)?;
+            let end = parse_time(&w.end)?;
+            if start == end {
+                return Err(format!("Window {}–{} is empty", w.start, w.end));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_active(&self, now: &NaiveDateTime) -> bool {
+        self.windows.iter().any(|w| w.contains(now))
+    }
+
+    /// Short form for traces and the UI, e.g. `mon,tue 09:00–17:00`
+    pub fn describe(&self) -> String {
+        self.windows
+            .iter()
+            .map(|w| {
+                let days = if w.days.is_empty() {
+                    "daily".to_string()
+                } else {
+                    w.days
+                        .iter()
+                        .map(|d| d.weekday().to_string().to_lowercase())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                };
+                format!("{} {}–{}", days, w.start, w.end)
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// The current local time, as schedules are written in it
+pub fn local_now() -> NaiveDateTime {
+    chrono::Local::now().naive_local()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn window(days: &[Day], start: &str, end: &str) -> Schedule {
+        Schedule {
+            windows: vec![TimeWindow {
+                days: days.to_vec(),
+                start: start.into(),
+                end: end.into(),
+            }],
+        }
+    }
+
+    /// `day` of the week starting Monday 2024-01-01, at `time`
+    fn at(day: u32, time: &str) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 1, day)
+            .unwrap()
+            .and_time(parse_time(time).unwrap())
+    }
+
+    #[test]
+    fn window_end_is_exclusive() {
+        let work = window(&[], "09:00", "17:00");
+        assert!(!work.is_active(&at(1, "08:59")));
+        assert!(work.is_active(&at(1, "09:00")));
+        assert!(work.is_active(&at(1, "16:59")));
+        assert!(!work.is_active(&at(1, "17:00")));
+    }
+
+    #[test]
+    fn days_limit_the_window() {
+        let weekdays = window(
+            &[Day::Mon, Day::Tue, Day::Wed, Day::Thu, Day::Fri],
+            "09:00",
+            "17:00",
+        );
+        assert!(weekdays.is_active(&at(5, "12:00")));
+        assert!(!weekdays.is_active(&at(6, "12:00")));
+        assert!(!weekdays.is_active(&at(7, "12:00")));
+        assert!(window(&[], "09:00", "17:00").is_active(&at(7, "12:00")));
+    }
+
+    #[test]
+    fn overnight_window_belongs_to_the_day_it_starts() {
+        let saturday_night = window(&[Day::Sat], "22:00", "06:00");
+        assert!(saturday_night.is_active(&at(6, "22:00")));
+        assert!(saturday_night.is_active(&at(6, "23:59")));
+        assert!(saturday_night.is_active(&at(7, "05:59")));
+        assert!(!saturday_night.is_active(&at(7, "06:00")));
+        assert!(!saturday_night.is_active(&at(7, "22:30")));
+        assert!(!saturday_night.is_active(&at(6, "05:00")));
+    }
+
+    #[test]
+    fn overnight_window_wraps_sunday_into_monday() {
+        let sunday_night = window(&[Day::Sun], "23:00", "01:00");
+        assert!(sunday_night.is_active(&at(7, "23:30")));
+        assert!(sunday_night.is_active(&at(8, "00:30")));
+        assert!(!sunday_night.is_active(&at(9, "00:30")));
+    }
+
+    #[test]
+    fn validate_rejects_empty_and_malformed_windows() {
+        assert!(window(&[], "09:00", "17:00").validate().is_ok());
+        assert!(window(&[], "22:00", "06:00").validate().is_ok());
+        assert!(Schedule {
+            windows: Vec::new()
+        }
+        .validate()
+        .is_err());
+        assert!(window(&[], "09:00", "09:00").validate().is_err());
+        assert!(window(&[], "9am", "17:00").validate().is_err());
+        assert!(window(&[], "09:00", "24:00").validate().is_err());
+    }
+
+    #[test]
+    fn malformed_window_never_matches() {
+        assert!(!window(&[], "09:00", "5pm").is_active(&at(1, "12:00")));
+    }
+}
@@ -0,0 +1,34 @@
+# Clipboard Guard bracketed-paste hook for bash (4.4+).
+# Add to ~/.bashrc:  eval "$(clipguard shell-init bash)"
+
+__clipguard_bracketed_paste() {
+  local pasted="" c report rc
+  # Readline hands us the start marker; read raw input up to the end marker.
+  while IFS= read -r -s -N 1 c </dev/tty; do
+    pasted+=$c
+    [[ $pasted == *$'\e[201~' ]] && break
+  done
+  pasted=${pasted%$'\e[201~'}
+  pasted=${pasted//$'\r'/$'\n'}
+
+  report=$(printf '%s' "$pasted" | @CLIPGUARD@ inspect --dest "${__CFBundleIdentifier:-}" 2>&1)
+  rc=$?
+  case $rc in
+    0) ;;
+    2)
+      printf '%s\n' "$report" >/dev/tty
+      read -r -p "clipguard: insert this paste? [y/N] " c </dev/tty
+      [[ $c == [yY]* ]] || return 0
+      ;;
+    3)
+      printf '%s\n' "$report" >/dev/tty
+      return 0
+      ;;
+    *) printf '%s\n' "$report" >/dev/tty ;;
+  esac
+  READLINE_LINE=${READLINE_LINE:0:READLINE_POINT}$pasted${READLINE_LINE:READLINE_POINT}
+  READLINE_POINT=$((READLINE_POINT + ${#pasted}))
+}
+
+bind 'set enable-bracketed-paste on'
+bind -x '"\e[200~": __clipguard_bracketed_paste'
@@ -0,0 +1,35 @@
+# Clipboard Guard bracketed-paste hook for fish (3.0+).
+# Add to ~/.config/fish/config.fish:  clipguard shell-init fish | source
+
+function __clipguard_bracketed_paste
+    # Read raw input up to the end marker instead of letting fish insert it.
+    set -l pasted ""
+    while read -l -n 1 -z c </dev/tty
+        set pasted "$pasted$c"
+        string match -q -- '*'\e'[201~' "$pasted"; and break
+    end
+    set pasted (string replace -- \e'[201~' '' "$pasted" | string collect)
+
+    set -l report (printf '%s' "$pasted" | @CLIPGUARD@ inspect --dest "$__CFBundleIdentifier" 2>&1 | string collect)
+    set -l rc $pipestatus[2]
+    switch $rc
+        case 0
+        case 2
+            printf '\n%s\n' "$report" >/dev/tty
+            read -l -P "clipguard: insert this paste? [y/N] " answer </dev/tty
+            if not string match -qi 'y*' -- "$answer"
+                commandline -f repaint
+                return
+            end
+        case 3
+            printf '\n%s\n' "$report" >/dev/tty
+            commandline -f repaint
+            return
+        case '*'
+            printf '\n%s\n' "$report" >/dev/tty
+    end
+    commandline -i -- "$pasted"
+    commandline -f repaint
+end
+
+bind \e\[200~ __clipguard_bracketed_paste
@@ -0,0 +1,75 @@
+use zeroize::Zeroizing;
+
+/// Largest snapshot kept, summed over every flavor
+pub const MAX_SNAPSHOT_BYTES: usize = 32 * 1024 * 1024;
+
+/// One representation of the clipboard's content, e.g. its HTML or its PNG
+#[derive(Clone)]
+pub struct Flavor<K> {
+    /// What the backend needs to put it back: a type identifier, format number or target
+    pub kind: K,
+    /// Wiped from memory when dropped
+    pub data: Zeroizing<Vec<u8>>,
+}
+
+/// Everything on the clipboard at one moment, held in memory only, so it can be put
+/// back byte for byte. No Debug or Serialize on purpose: it may hold secrets.
+#[derive(Clone)]
+pub struct Snapshot<K> {
+    flavors: Vec<Flavor<K>>,
+    size: usize,
+    complete: bool,
+}
+
+impl<K> Default for Snapshot<K> {
+    fn default() -> Self {
+        Self {
+            flavors: Vec::new(),
+            size: 0,
+            complete: true,
+        }
+    }
+}
+
+impl<K> Snapshot<K> {
+    /// Add a flavor, unless it would take the snapshot past `MAX_SNAPSHOT_BYTES`
+    pub fn push(&mut self, kind: K, data: Vec<u8>) {
+        let data = Zeroizing::new(data);
+        if self.size + data.len() > MAX_SNAPSHOT_BYTES {
+            self.complete = false;
+            return;
+        }
+        self.size += data.len();
+        self.flavors.push(Flavor { kind, data });
+    }
+
+    /// A flavor couldn't be read or was left out for size
+    pub fn mark_incomplete(&mut self) {
+        self.complete = false;
+    }
+
+    /// Whether restoring puts back exactly what was there; rewrites should skip the
+    /// clipboard otherwise rather than lose part of it
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    pub fn flavors(&self) -> &[Flavor<K>] {
+        &self.flavors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oversized_snapshot_is_incomplete() {
+        let mut snapshot = Snapshot::default();
+        snapshot.push("text/plain", vec![b'a'; 16]);
+        snapshot.push("image/png", vec![0; MAX_SNAPSHOT_BYTES]);
+        assert!(!snapshot.is_complete());
+        assert_eq!(snapshot.flavors().len(), 1);
+        assert_eq!(*snapshot.flavors()[0].data, vec![b'a'; 16]);
+    }
+}
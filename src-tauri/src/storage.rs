@@ -0,0 +1,226 @@
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+/// Rotating backups kept next to each file: `name.1` (newest) … `name.N`
+pub const BACKUP_COUNT: usize = 3;
+
+/// Emitted when a corrupt file was replaced by one of its backups
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recovery {
+    pub file: String,
+    pub backup: String,
+    pub error: String,
+}
+
+pub struct Loaded<T> {
+    pub value: T,
+    pub recovery: Option<Recovery>,
+}
+
+pub fn data_path(app: &tauri::AppHandle, file_name: &str) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|d| d.join(file_name))
+}
+
+/// `path` with `suffix` appended to the file name, e.g. `rules.json` → `rules.json.tmp`
+pub fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn backup_path(path: &Path, n: usize) -> PathBuf {
+    sibling(path, &format!(".{}", n))
+}
+
+/// Write via temp file + fsync + rename so a crash leaves either the old or the new
+/// contents, never a mix. The previous file is rotated into the backups first.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let dir = path.parent().ok_or("invalid path")?;
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+
+    let tmp = sibling(path, ".tmp");
+    let mut file = File::create(&tmp).map_err(|e| e.to_string())?;
+    file.write_all(contents).map_err(|e| e.to_string())?;
+    file.sync_all().map_err(|e| e.to_string())?;
+    drop(file);
+
+    rotate_backups(path).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, path).map_err(|e| e.to_string())?;
+    sync_dir(dir);
+    Ok(())
+}
+
+fn rotate_backups(path: &Path) -> io::Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    for n in (1..BACKUP_COUNT).rev() {
+        let from = backup_path(path, n);
+        if from.exists() {
+            fs::rename(&from, backup_path(path, n + 1))?;
+        }
+    }
+    fs::copy(path, backup_path(path, 1))?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) {
+    if let Ok(d) = File::open(dir) {
+        let _ = d.sync_all();
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) {}
+
+/// Read and parse `path`. Returns `Ok(None)` when the file doesn't exist yet.
+///
+/// If it can't be parsed, the newest backup that does parse is restored in its place and the
+/// corrupt file is kept as `name.invalid.bak`. Fails only when no backup is usable either.
+pub fn read_with_recovery<T>(
+    path: &Path,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> Result<Option<Loaded<T>>, String> {
+    let error = match fs::read_to_string(path) {
+        Ok(text) => match parse(&text) {
+            Ok(value) => {
+                return Ok(Some(Loaded {
+                    value,
+                    recovery: None,
+                }))
+            }
+            Err(e) => format!("{}: {}", path.display(), e),
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => format!("Could not read {}: {}", path.display(), e),
+    };
+
+    let invalid = sibling(path, ".invalid.bak");
+    for n in 1..=BACKUP_COUNT {
+        let backup = backup_path(path, n);
+        let Ok(text) = fs::read_to_string(&backup) else {
+            continue;
+        };
+        let Ok(value) = parse(&text) else {
+            continue;
+        };
+        let _ = fs::rename(path, &invalid);
+        fs::copy(&backup, path).map_err(|e| e.to_string())?;
+        return Ok(Some(Loaded {
+            value,
+            recovery: Some(Recovery {
+                file: path.display().to_string(),
+                backup: backup.display().to_string(),
+                error,
+            }),
+        }));
+    }
+
+    let _ = fs::copy(path, &invalid);
+    Err(error)
+}
+
+/// Read and parse `path` like `read_with_recovery`, falling back to the newest backup that
+/// parses, but without changing anything on disk. For readers that don't own the file.
+pub fn read_only<T>(
+    path: &Path,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> Result<Option<T>, String> {
+    let error = match fs::read_to_string(path) {
+        Ok(text) => match parse(&text) {
+            Ok(value) => return Ok(Some(value)),
+            Err(e) => format!("{}: {}", path.display(), e),
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => format!("Could not read {}: {}", path.display(), e),
+    };
+    (1..=BACKUP_COUNT)
+        .find_map(|n| {
+            let text = fs::read_to_string(backup_path(path, n)).ok()?;
+            parse(&text).ok()
+        })
+        .map(Some)
+        .ok_or(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_path(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("clipguard-storage-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("data.json")
+    }
+
+    fn parse(json: &str) -> Result<u64, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    fn read(path: &Path) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn write_atomic_rotates_backups() {
+        let path = test_path("rotate");
+        for n in 1..=5 {
+            write_atomic(&path, n.to_string().as_bytes()).unwrap();
+        }
+        assert_eq!(read(&path), "5");
+        assert_eq!(read(&backup_path(&path, 1)), "4");
+        assert_eq!(read(&backup_path(&path, 2)), "3");
+        assert_eq!(read(&backup_path(&path, BACKUP_COUNT)), "2");
+        assert!(!backup_path(&path, BACKUP_COUNT + 1).exists());
+        assert!(!sibling(&path, ".tmp").exists());
+    }
+
+    #[test]
+    fn recovery_restores_the_newest_backup_that_parses() {
+        let path = test_path("recover");
+        for contents in ["1", "2", "3"] {
+            write_atomic(&path, contents.as_bytes()).unwrap();
+        }
+        // Newest backup (2) is damaged too; the next one (1) should be restored
+        fs::write(backup_path(&path, 1), "{").unwrap();
+        fs::write(&path, "not json").unwrap();
+
+        let loaded = read_with_recovery(&path, parse).unwrap().unwrap();
+        assert_eq!(loaded.value, 1);
+        let recovery = loaded.recovery.unwrap();
+        assert_eq!(recovery.backup, backup_path(&path, 2).display().to_string());
+        assert_eq!(read(&path), "1");
+        assert_eq!(read(&sibling(&path, ".invalid.bak")), "not json");
+    }
+
+    #[test]
+    fn corrupt_file_without_backups_is_kept() {
+        let path = test_path("unrecoverable");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "not json").unwrap();
+
+        assert!(read_with_recovery(&path, parse).is_err());
+        assert_eq!(read(&path), "not json");
+        assert_eq!(read(&sibling(&path, ".invalid.bak")), "not json");
+    }
+
+    #[test]
+    fn read_only_falls_back_without_touching_disk() {
+        let path = test_path("read-only");
+        write_atomic(&path, b"1").unwrap();
+        write_atomic(&path, b"2").unwrap();
+        fs::write(&path, "not json").unwrap();
+
+        assert_eq!(read_only(&path, parse).unwrap(), Some(1));
+        assert_eq!(read(&path), "not json");
+        assert!(!sibling(&path, ".invalid.bak").exists());
+        assert_eq!(read_only(&test_path("missing"), parse).unwrap(), None);
+    }
+}
@@ -0,0 +1,155 @@
+use std::fs;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use notify::{Event, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::clipboard::ClipboardState;
+use crate::config;
+use crate::policy;
+use crate::rules::{self, RuleDiagnostic};
+
+/// Editors and our own atomic saves touch a file several times; let them settle first
+const DEBOUNCE_MS: u64 = 250;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RulesReloaded {
+    pub applied: bool,
+    pub error: Option<String>,
+    pub diagnostics: Vec<RuleDiagnostic>,
+}
+
+#[derive(Default)]
+struct Changed {
+    rules: bool,
+    config: bool,
+}
+
+impl Changed {
+    fn add(&mut self, event: notify::Result<Event>) {
+        let Ok(event) = event else {
+            return;
+        };
+        if event.kind.is_access() {
+            return;
+        }
+        for path in &event.paths {
+            match path.file_name().and_then(|n| n.to_str()) {
+                Some("rules.json") => self.rules = true,
+                Some("config.json") => self.config = true,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Watch the app data dir and reload rules.json / config.json when they change on disk
+pub fn start(app: AppHandle, state: Arc<Mutex<ClipboardState>>) {
+    let Ok(dir) = app.path().app_data_dir() else {
+        return;
+    };
+
+    thread::spawn(move || {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = match notify::recommended_watcher(tx) {
+            Ok(w) => w,
+            Err(e) => {
+                eprintln!("watcher: failed to create: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = fs::create_dir_all(&dir)
+            .map_err(notify::Error::io)
+            .and_then(|_| watcher.watch(&dir, RecursiveMode::NonRecursive))
+        {
+            eprintln!("watcher: failed to watch {}: {}", dir.display(), e);
+            return;
+        }
+
+        while let Ok(event) = rx.recv() {
+            let mut changed = Changed::default();
+            changed.add(event);
+            while let Ok(event) = rx.recv_timeout(Duration::from_millis(DEBOUNCE_MS)) {
+                changed.add(event);
+            }
+
+            if changed.rules {
+                reload_rules(&app, &state);
+            }
+            if changed.config {
+                reload_config(&app, &state);
+            }
+        }
+    });
+}
+
+fn reload_rules(app: &AppHandle, state: &Arc<Mutex<ClipboardState>>) {
+    let report = match rules::read(app) {
+        Ok(on_disk) => {
+            let Ok(mut s) = state.lock() else {
+                return;
+            };
+            let user_rules = rules::stamp(&policy::user_rules(&s.rules), on_disk.clone());
+            let new_rules = policy::merge(&s.policy, user_rules.clone());
+            let diagnostics = rules::lint(&new_rules);
+            // Our own saves come back through here too
+            if s.rules == new_rules {
+                return;
+            }
+            // A hand edit gets no further than the settings window when rules are locked
+            if let Err(e) = policy::apply_user_edit(&s.policy, &s.rules, &new_rules) {
+                RulesReloaded {
+                    applied: false,
+                    error: Some(e),
+                    diagnostics,
+                }
+            } else if let Some(err) = rules::first_error(&diagnostics) {
+                RulesReloaded {
+                    applied: false,
+                    error: Some(format!("Rule {}: {}", err.index + 1, err.message)),
+                    diagnostics,
+                }
+            } else {
+                // Hand-edited rules get ids and timestamps written back
+                if user_rules != on_disk {
+                    if let Err(e) = rules::save(app, &user_rules) {
+                        eprintln!("watcher: could not save rule ids: {}", e);
+                    }
+                }
+                s.rules = new_rules;
+                s.rule_diagnostics = diagnostics.clone();
+                s.rules_error = None;
+                s.recheck = true;
+                RulesReloaded {
+                    applied: true,
+                    error: None,
+                    diagnostics,
+                }
+            }
+        }
+        Err(e) => RulesReloaded {
+            applied: false,
+            error: Some(e),
+            diagnostics: Vec::new(),
+        },
+    };
+    let _ = app.emit("rules-reloaded", &report);
+}
+
+fn reload_config(app: &AppHandle, state: &Arc<Mutex<ClipboardState>>) {
+    match config::read(app) {
+        Ok(new_config) => {
+            if let Ok(mut s) = state.lock() {
+                s.config = new_config;
+                s.config_error = None;
+                // A changed block strategy applies to the app already in front
+                s.recheck = true;
+            }
+        }
+        Err(e) => eprintln!("watcher: keeping current config: {}", e),
+    }
+}
@@ -2,6 +2,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 import { enable, disable, isEnabled } from '@tauri-apps/plugin-autostart';
+import { open, save } from '@tauri-apps/plugin-dialog';
 import {
     useEffect,
     useState,
@@ -13,9 +14,65 @@ import { AppPickerModal } from './AppPickerModal';
 import type { AppBundleInfo } from './AppPickerModal';
 import './App.css';
 
+interface Origin {
+    app_id: string | null;
+    app_name: string | null;
+    first_seen: number;
+}
+
 interface ClipboardEvent {
     source_app_id: string | null;
     source_app_name: string | null;
+    concealed: boolean;
+    transient: boolean;
+    origin: Origin | null;
+    label: string | null;
+    copied_at: number | null;
+}
+
+interface PredicateTrace {
+    name: string;
+    expected: string | null;
+    actual: string | null;
+    matched: boolean;
+}
+
+interface RuleTrace {
+    index: number;
+    rule: BlockRule;
+    predicates: PredicateTrace[];
+    matched: boolean;
+    winner: boolean;
+}
+
+interface Finding {
+    detector: string;
+    severity: 'low' | 'medium' | 'high';
+    message: string;
+    start: number;
+    end: number;
+}
+
+interface ZoneTrace {
+    source_zone: string | null;
+    dest_zone: string | null;
+    flow: number | null;
+    action: RuleAction | null;
+    overridden: boolean;
+}
+
+interface Evaluation {
+    source_app_id: string | null;
+    dest_app_id: string;
+    decision: RuleAction | null;
+    matched: number | null;
+    rule_id: string | null;
+    override_id: string | null;
+    reason: string;
+    trace: RuleTrace[];
+    zone: ZoneTrace | null;
+    stale_mins: number | null;
+    findings: Finding[];
 }
 
 interface PasteWarning {
@@ -24,45 +81,261 @@ interface PasteWarning {
     dest_app_id: string | null;
     dest_app_name: string | null;
     blocked: boolean;
+    rule_id: string | null;
+    explanation: Evaluation;
 }
 
 interface TimestampedWarning extends PasteWarning {
     ts: number;
 }
 
-type RuleAction = 'notify' | 'block';
+type RuleAction =
+    | 'notify'
+    | 'block'
+    | 'confirm'
+    | 'sanitize'
+    | 'redact'
+    | 'allow';
+
+type Transform =
+    | 'strip_hidden'
+    | 'strip_ansi'
+    | 'ascii_punctuation'
+    | 'trim_trailing_newline'
+    | 'collapse_lines';
+
+const TRANSFORMS: { value: Transform; label: string }[] = [
+    { value: 'strip_hidden', label: 'Hidden characters' },
+    { value: 'strip_ansi', label: 'Escape sequences' },
+    { value: 'ascii_punctuation', label: 'Smart quotes & dashes' },
+    { value: 'trim_trailing_newline', label: 'Trailing newline' },
+    { value: 'collapse_lines', label: 'Join lines' },
+];
+
+// What a sanitize rule without its own list applies; matches the backend
+const DEFAULT_TRANSFORMS: Transform[] = [
+    'strip_ansi',
+    'strip_hidden',
+    'ascii_punctuation',
+    'trim_trailing_newline',
+];
+
+const ACTION_LABELS: Record<RuleAction, string> = {
+    notify: 'Notify',
+    block: 'Block',
+    confirm: 'Confirm',
+    sanitize: 'Sanitize',
+    redact: 'Redact',
+    allow: 'Allow',
+};
+
+// Order the action toggle cycles through
+const NEXT_ACTION: Record<RuleAction, RuleAction> = {
+    notify: 'block',
+    block: 'confirm',
+    confirm: 'sanitize',
+    sanitize: 'redact',
+    redact: 'allow',
+    allow: 'notify',
+};
+
+interface PendingBlock {
+    source_app_id: string | null;
+    source_app_name: string | null;
+    dest_app_id: string;
+    dest_app_name: string | null;
+    rule_id: string | null;
+    managed?: boolean;
+}
+
+type BlockChoice = 'allow_once' | 'always_allow' | 'keep_blocking';
 
 interface BlockRule {
+    id: string;
+    name?: string | null;
+    description?: string | null;
     from_app_id: string | null;
     from_app_name: string | null;
     to_app_id: string | null;
     to_app_name: string | null;
+    from_group?: string | null;
+    to_group?: string | null;
+    match_source?: 'immediate' | 'original' | 'either';
     action: RuleAction;
+    enabled: boolean;
+    tags?: string[];
+    created_at?: number | null;
+    modified_at?: number | null;
+    expires_at?: number | null;
+    schedule?: Schedule | null;
+    transforms?: Transform[];
+    concealed?: boolean | null;
+    transient?: boolean | null;
+    min_label?: string | null;
+    outside_group?: string | null;
+    max_age?: number | null;
+    min_age?: number | null;
+    managed?: boolean;
 }
 
-interface BlockRuleWithId extends BlockRule {
+interface TimeWindow {
+    days?: string[];
+    start: string;
+    end: string;
+}
+
+interface Schedule {
+    windows: TimeWindow[];
+}
+
+function describeSchedule(schedule: Schedule): string {
+    return schedule.windows
+        .map((w) => {
+            const days = w.days?.length ? w.days.join(', ') : 'Daily';
+            return `${days} ${w.start}–${w.end}`;
+        })
+        .join('; ');
+}
+
+type OverrideTarget =
+    | {
+          kind: 'allow_pair';
+          from_app_id: string | null;
+          from_app_name: string | null;
+          to_app_id: string;
+          to_app_name: string | null;
+      }
+    | { kind: 'snooze_rule'; rule_id: string };
+
+type Override = OverrideTarget & {
     id: string;
+    until: number;
+    created_at: number;
+};
+
+const SNOOZE_CHOICES: { label: string; until: () => number }[] = [
+    { label: '15 minutes', until: () => Date.now() / 1000 + 15 * 60 },
+    { label: '1 hour', until: () => Date.now() / 1000 + 60 * 60 },
+    {
+        label: 'Until tomorrow',
+        until: (): number => {
+            const d = new Date();
+            d.setHours(24, 0, 0, 0);
+            return d.getTime() / 1000;
+        },
+    },
+];
+
+function formatTime(unixSecs: number): string {
+    return new Date(unixSecs * 1000).toLocaleTimeString([], {
+        hour: '2-digit',
+        minute: '2-digit',
+    });
 }
 
-let ruleIdCounter = 0;
-function nextRuleId(): string {
-    ruleIdCounter += 1;
-    return String(ruleIdCounter);
+type BlockStrategy = 'key_hook' | 'replace_clipboard';
+
+interface PolicySettings {
+    lock_enabled: boolean;
+    lock_user_rules: boolean;
+    block_strategy?: BlockStrategy;
 }
 
-function withId(rule: BlockRule): BlockRuleWithId {
-    return { ...rule, id: nextRuleId() };
+interface RuleDiagnostic {
+    index: number;
+    level: 'error' | 'warning';
+    code: string;
+    message: string;
+    related: number | null;
+}
+
+interface RulesReloaded {
+    applied: boolean;
+    error: string | null;
+    diagnostics: RuleDiagnostic[];
+}
+
+interface StorageRecovery {
+    file: string;
+    backup: string;
+    error: string;
+}
+
+type ImportMode = 'merge' | 'replace';
+
+interface ImportPreview {
+    added: BlockRule[];
+    removed: BlockRule[];
+    unchanged: number;
+    rules: BlockRule[];
+    error: string | null;
+}
+
+interface PendingImport {
+    path: string;
+    mode: ImportMode;
+    preview: ImportPreview;
+}
+
+const RULE_FILE_FILTERS = [
+    { name: 'Rule files', extensions: ['toml', 'yaml', 'yml'] },
+];
+
+function describeRule(rule: BlockRule): string {
+    const from = rule.from_app_name ?? rule.from_app_id ?? 'Any App';
+    const to = rule.to_app_name ?? rule.to_app_id ?? 'All Apps';
+    return `${from} → ${to} (${rule.action})`;
+}
+
+// Ids are made here so React keys are stable; the backend keeps them
+function newRule(): BlockRule {
+    return {
+        id: crypto.randomUUID(),
+        from_app_id: null,
+        from_app_name: null,
+        to_app_id: null,
+        to_app_name: null,
+        action: 'notify',
+        enabled: true,
+    };
 }
 
 function App(): ReactElement {
     const [isWindowsPlatform, setIsWindowsPlatform] = useState(false);
+    const [holdsPastes, setHoldsPastes] = useState(true);
+    const [detectionLimit, setDetectionLimit] = useState<string | null>(
+        null,
+    );
     const [guardEnabled, setGuardEnabled] = useState(true);
     const [autostartEnabled, setAutostartEnabled] = useState(false);
     const [lastSource, setLastSource] = useState<ClipboardEvent | null>(null);
     const [recentWarnings, setRecentWarnings] = useState<TimestampedWarning[]>(
         [],
     );
-    const [rules, setRules] = useState<BlockRuleWithId[]>([]);
+    const [rules, setRules] = useState<BlockRule[]>([]);
+    const [diagnostics, setDiagnostics] = useState<RuleDiagnostic[]>([]);
+    const [rulesError, setRulesError] = useState<string | null>(null);
+    const [configError, setConfigError] = useState<string | null>(null);
+    const [recovery, setRecovery] = useState<StorageRecovery | null>(null);
+    const [reloadError, setReloadError] = useState<string | null>(null);
+    const [policyAlert, setPolicyAlert] = useState<string | null>(null);
+    const [pendingImport, setPendingImport] = useState<PendingImport | null>(
+        null,
+    );
+    const [overrides, setOverrides] = useState<Override[]>([]);
+    const [activeRuleIds, setActiveRuleIds] = useState<Set<string>>(
+        new Set(),
+    );
+    const [overrideError, setOverrideError] = useState<string | null>(null);
+    const [pendingBlock, setPendingBlock] = useState<PendingBlock | null>(null);
+    const [blockError, setBlockError] = useState<string | null>(null);
+    const [importError, setImportError] = useState<string | null>(null);
+    const [policySettings, setPolicySettings] = useState<PolicySettings>({
+        lock_enabled: false,
+        lock_user_rules: false,
+    });
+    const [blockStrategy, setBlockStrategy] =
+        useState<BlockStrategy>('key_hook');
     const [accessibilityGranted, setAccessibilityGranted] = useState(false);
     const [appList, setAppList] = useState<AppBundleInfo[]>([]);
     const [appPickerOpen, setAppPickerOpen] = useState(false);
@@ -75,22 +348,41 @@ function App(): ReactElement {
 
         void invoke<boolean>('get_enabled').then(setGuardEnabled);
         void invoke<boolean>('is_windows_platform').then(setIsWindowsPlatform);
+        void invoke<boolean>('holds_pastes').then(setHoldsPastes);
+        void invoke<string | null>('app_detection_limit').then(
+            setDetectionLimit,
+        );
         void isEnabled().then(setAutostartEnabled);
         void invoke<BlockRule[]>('get_rules').then((loaded) => {
-            if (loaded.length === 0) {
-                setRules([
-                    withId({
-                        from_app_id: null,
-                        from_app_name: null,
-                        to_app_id: null,
-                        to_app_name: null,
-                        action: 'notify',
-                    }),
-                ]);
-            } else {
-                setRules(loaded.map(withId));
-            }
+            setRules(loaded.length === 0 ? [newRule()] : loaded);
+        });
+        void invoke<RuleDiagnostic[]>('get_rule_diagnostics').then(
+            setDiagnostics,
+        );
+        void invoke<string | null>('get_rules_error').then(setRulesError);
+        void invoke<string | null>('get_config_error').then(setConfigError);
+        void invoke<Override[]>('get_overrides').then(setOverrides);
+        void invoke<PendingBlock | null>('get_pending_block').then(
+            setPendingBlock,
+        );
+
+        // Schedules and snoozes change which rules apply without any event
+        const refreshActive = (): void => {
+            void invoke<BlockRule[]>('get_active_rules').then((active) => {
+                setActiveRuleIds(new Set(active.map((r) => r.id)));
+            });
+        };
+        refreshActive();
+        const activeTimer = setInterval(refreshActive, 30_000);
+        cleanups.push((): void => {
+            clearInterval(activeTimer);
         });
+        void invoke<PolicySettings>('get_policy_settings').then(
+            setPolicySettings,
+        );
+        void invoke<BlockStrategy>('get_block_strategy').then(
+            setBlockStrategy,
+        );
         void invoke<boolean>('check_accessibility').then(
             setAccessibilityGranted,
         );
@@ -105,6 +397,37 @@ function App(): ReactElement {
             );
         }).then((f) => cleanups.push(f));
 
+        void listen<RulesReloaded>('rules-reloaded', (e) => {
+            if (e.payload.applied) {
+                setReloadError(null);
+                setDiagnostics(e.payload.diagnostics);
+                void invoke<BlockRule[]>('get_rules').then((loaded) => {
+                    setRules(loaded);
+                });
+            } else {
+                setReloadError(e.payload.error);
+            }
+        }).then((f) => cleanups.push(f));
+
+        void listen<Override[]>('overrides-changed', (e) => {
+            setOverrides(
+                e.payload.filter((o) => o.until > Date.now() / 1000),
+            );
+        }).then((f) => cleanups.push(f));
+
+        void listen<PendingBlock | null>('pending-block-changed', (e) => {
+            setPendingBlock(e.payload);
+            setBlockError(null);
+        }).then((f) => cleanups.push(f));
+
+        void listen<string>('policy-alert', (e) => {
+            setPolicyAlert(e.payload);
+        }).then((f) => cleanups.push(f));
+
+        void listen<StorageRecovery>('storage-recovered', (e) => {
+            setRecovery(e.payload);
+        }).then((f) => cleanups.push(f));
+
         void listen<boolean>('guard-toggled', (e) => {
             setGuardEnabled(e.payload);
         }).then((f) => cleanups.push(f));
@@ -141,9 +464,125 @@ function App(): ReactElement {
         setAutostartEnabled(!autostartEnabled);
     }
 
-    async function saveRules(updated: BlockRuleWithId[]): Promise<void> {
+    async function changeBlockStrategy(next: BlockStrategy): Promise<void> {
+        await invoke('set_block_strategy', { strategy: next });
+        setBlockStrategy(next);
+    }
+
+    async function saveRules(updated: BlockRule[]): Promise<void> {
         setRules(updated);
-        await invoke('set_rules', { newRules: updated });
+        setDiagnostics(
+            await invoke<RuleDiagnostic[]>('lint_rules', { rules: updated }),
+        );
+        // Rejected when a rule has errors; those are shown inline until fixed
+        await invoke('set_rules', { newRules: updated }).then(
+            (): void => {
+                setRulesError(null);
+            },
+            (): void => undefined,
+        );
+    }
+
+    async function exportRules(): Promise<void> {
+        const path = await save({
+            defaultPath: 'clipguard-rules.toml',
+            filters: RULE_FILE_FILTERS,
+        });
+        if (path === null) return;
+        await invoke('export_rules', { path }).then(
+            (): void => {
+                setImportError(null);
+            },
+            (e: unknown): void => {
+                setImportError(String(e));
+            },
+        );
+    }
+
+    async function previewImport(
+        path: string,
+        mode: ImportMode,
+    ): Promise<void> {
+        await invoke<ImportPreview>('preview_import', { path, mode }).then(
+            (preview): void => {
+                setImportError(null);
+                setPendingImport({ path, mode, preview });
+            },
+            (e: unknown): void => {
+                setImportError(String(e));
+                setPendingImport(null);
+            },
+        );
+    }
+
+    async function chooseImport(): Promise<void> {
+        const path = await open({
+            multiple: false,
+            filters: RULE_FILE_FILTERS,
+        });
+        if (path === null) return;
+        await previewImport(path, 'merge');
+    }
+
+    async function applyImport(pending: PendingImport): Promise<void> {
+        await invoke<RuleDiagnostic[]>('import_rules', {
+            path: pending.path,
+            mode: pending.mode,
+        }).then(
+            async (applied): Promise<void> => {
+                setPendingImport(null);
+                setDiagnostics(applied);
+                setRulesError(null);
+                const loaded = await invoke<BlockRule[]>('get_rules');
+                setRules(loaded);
+            },
+            (e: unknown): void => {
+                setImportError(String(e));
+            },
+        );
+    }
+
+    async function addOverride(
+        target: OverrideTarget,
+        until: number,
+    ): Promise<void> {
+        await invoke<Override>('add_override', {
+            target,
+            until: Math.floor(until),
+        }).then(
+            (added): void => {
+                setOverrideError(null);
+                setOverrides((prev) => [...prev, added]);
+            },
+            (e: unknown): void => {
+                setOverrideError(String(e));
+            },
+        );
+    }
+
+    async function removeOverride(id: string): Promise<void> {
+        await invoke('remove_override', { id });
+        setOverrides((prev) => prev.filter((o) => o.id !== id));
+    }
+
+    async function resolveBlockedPaste(choice: BlockChoice): Promise<void> {
+        await invoke('resolve_blocked_paste', { choice }).then(
+            (): void => {
+                setBlockError(null);
+            },
+            (e: unknown): void => {
+                setBlockError(String(e));
+            },
+        );
+    }
+
+    function describeOverride(o: Override): string {
+        if (o.kind === 'allow_pair') {
+            const from = o.from_app_name ?? o.from_app_id ?? 'Any App';
+            return `${from} → ${o.to_app_name ?? o.to_app_id} allowed`;
+        }
+        const rule = rules.find((r) => r.id === o.rule_id);
+        return `${rule ? (rule.name ?? describeRule(rule)) : 'Rule'} snoozed`;
     }
 
     const openAppPicker = useCallback(
@@ -169,7 +608,7 @@ function App(): ReactElement {
         [closeAppPicker],
     );
 
-    function updateRule(index: number, patch: Partial<BlockRuleWithId>): void {
+    function updateRule(index: number, patch: Partial<BlockRule>): void {
         const updated = rules.map((r, i) =>
             i === index ? { ...r, ...patch } : r,
         );
@@ -181,16 +620,7 @@ function App(): ReactElement {
     }
 
     function addRule(): void {
-        void saveRules([
-            ...rules,
-            withId({
-                from_app_id: null,
-                from_app_name: null,
-                to_app_id: null,
-                to_app_name: null,
-                action: 'notify',
-            }),
-        ]);
+        void saveRules([...rules, newRule()]);
     }
 
     function browseFrom(index: number): void {
@@ -198,6 +628,7 @@ function App(): ReactElement {
             updateRule(index, {
                 from_app_id: app.bundle_id,
                 from_app_name: app.name,
+                from_group: null,
             });
         });
     }
@@ -207,30 +638,71 @@ function App(): ReactElement {
             updateRule(index, {
                 to_app_id: app.bundle_id,
                 to_app_name: app.name,
+                to_group: null,
             });
         });
     }
 
     function clearFrom(index: number): void {
-        updateRule(index, { from_app_id: null, from_app_name: null });
+        updateRule(index, {
+            from_app_id: null,
+            from_app_name: null,
+            from_group: null,
+        });
     }
 
     function clearTo(index: number): void {
-        updateRule(index, { to_app_id: null, to_app_name: null });
+        updateRule(index, {
+            to_app_id: null,
+            to_app_name: null,
+            to_group: null,
+        });
     }
 
     function toggleAction(index: number): void {
-        const current = rules[index].action;
+        let action = NEXT_ACTION[rules[index].action];
+        // Without a way to hold the paste, Confirm would only notify
+        if (action === 'confirm' && !holdsPastes) {
+            action = NEXT_ACTION[action];
+        }
+        updateRule(index, { action });
+    }
+
+    function transformsOf(rule: BlockRule): Transform[] {
+        return rule.transforms?.length ? rule.transforms : DEFAULT_TRANSFORMS;
+    }
+
+    function toggleTransform(index: number, transform: Transform): void {
+        const current = transformsOf(rules[index]);
         updateRule(index, {
-            action: current === 'notify' ? 'block' : 'notify',
+            transforms: current.includes(transform)
+                ? current.filter((t) => t !== transform)
+                : [...current, transform],
         });
     }
 
-    function isInvalidRule(r: BlockRuleWithId): boolean {
-        return r.from_app_id === null && r.to_app_id === null;
+    function ruleName(id: string | null): string | null {
+        return rules.find((r) => r.id === id)?.name ?? null;
     }
 
-    const hasBlockRules = rules.some((r) => r.action === 'block');
+    function isReadOnly(rule: BlockRule): boolean {
+        return rule.managed === true || policySettings.lock_user_rules;
+    }
+
+    function diagnosticsFor(index: number): RuleDiagnostic[] {
+        return diagnostics.filter((d) => d.index === index);
+    }
+
+    function isInvalidRule(index: number): boolean {
+        return diagnosticsFor(index).some((d) => d.level === 'error');
+    }
+
+    // Replacing the clipboard doesn't need the key hook, so only Confirm rules do then
+    const hasBlockRules = rules.some(
+        (r) =>
+            r.action === 'confirm' ||
+            (r.action === 'block' && blockStrategy === 'key_hook'),
+    );
 
     async function refreshAccessibility(): Promise<void> {
         const granted = await invoke<boolean>('check_accessibility');
@@ -247,6 +719,7 @@ function App(): ReactElement {
                     <button
                         type="button"
                         className={guardEnabled ? 'btn-on' : 'btn-off'}
+                        disabled={guardEnabled && policySettings.lock_enabled}
                         onClick={(): void => {
                             void toggleGuard();
                         }}
@@ -266,6 +739,24 @@ function App(): ReactElement {
                         {autostartEnabled ? 'On' : 'Off'}
                     </button>
                 </div>
+                <div className="row space-between">
+                    <span>Blocking method</span>
+                    <select
+                        className="rule-snooze"
+                        value={blockStrategy}
+                        disabled={policySettings.block_strategy !== undefined}
+                        onChange={(e): void => {
+                            void changeBlockStrategy(
+                                e.target.value as BlockStrategy,
+                            );
+                        }}
+                    >
+                        <option value="key_hook">Catch the paste key</option>
+                        <option value="replace_clipboard">
+                            Replace the clipboard
+                        </option>
+                    </select>
+                </div>
             </section>
 
             {hasBlockRules && (
@@ -276,7 +767,7 @@ function App(): ReactElement {
                         <span>
                             {accessibilityGranted
                                 ? 'Accessibility permission granted'
-                                : 'Block rules require Accessibility permission'}
+                                : 'Block and confirm rules require Accessibility permission'}
                         </span>
                         {!accessibilityGranted && (
                             <div className="permission-actions">
@@ -305,9 +796,124 @@ function App(): ReactElement {
                     </div>
                     {!accessibilityGranted && (
                         <p className="muted">
-                            Without it, block rules will only notify.
+                            Without it, block and confirm rules will only notify.
+                        </p>
+                    )}
+                </section>
+            )}
+
+            {detectionLimit && (
+                <section className="permission-banner warning">
+                    <span>Rules can't see every app</span>
+                    <p className="muted">{detectionLimit}</p>
+                </section>
+            )}
+
+            {pendingBlock && (
+                <section className="permission-banner warning">
+                    <div className="row space-between">
+                        <span>
+                            Paste blocked:{' '}
+                            {pendingBlock.source_app_name ??
+                                pendingBlock.source_app_id ??
+                                'Unknown app'}{' '}
+                            →{' '}
+                            {pendingBlock.dest_app_name ??
+                                pendingBlock.dest_app_id}
+                        </span>
+                        <div className="permission-actions">
+                            <button
+                                type="button"
+                                className="btn-permission"
+                                disabled={pendingBlock.managed}
+                                onClick={(): void => {
+                                    void resolveBlockedPaste('allow_once');
+                                }}
+                            >
+                                Allow once
+                            </button>
+                            <button
+                                type="button"
+                                className="btn-refresh"
+                                disabled={
+                                    pendingBlock.managed ||
+                                    policySettings.lock_user_rules
+                                }
+                                onClick={(): void => {
+                                    void resolveBlockedPaste('always_allow');
+                                }}
+                            >
+                                Always allow
+                            </button>
+                            <button
+                                type="button"
+                                className="btn-refresh"
+                                onClick={(): void => {
+                                    void resolveBlockedPaste('keep_blocking');
+                                }}
+                            >
+                                Keep blocking
+                            </button>
+                        </div>
+                    </div>
+                    {pendingBlock.managed && (
+                        <p className="muted">
+                            Blocked by your organization, so it can't be allowed
+                            here.
                         </p>
                     )}
+                    {blockError && <p className="rule-error">{blockError}</p>}
+                </section>
+            )}
+
+            {recovery && (
+                <section className="permission-banner warning">
+                    <span>Settings restored from a backup</span>
+                    <p className="muted">
+                        {recovery.file} was damaged ({recovery.error}) and was
+                        replaced with {recovery.backup}.
+                    </p>
+                </section>
+            )}
+
+            {policyAlert && (
+                <section className="permission-banner warning">
+                    <span>Policy update rejected</span>
+                    <p className="muted">
+                        {policyAlert}. The last verified policy is still in
+                        force.
+                    </p>
+                </section>
+            )}
+
+            {reloadError && (
+                <section className="permission-banner warning">
+                    <span>rules.json changed on disk but was not applied</span>
+                    <p className="muted">
+                        {reloadError}. The previous rules are still active.
+                    </p>
+                </section>
+            )}
+
+            {configError && (
+                <section className="permission-banner warning">
+                    <span>Saved settings could not be loaded</span>
+                    <p className="muted">
+                        {configError}. Default settings are active; changing
+                        how pastes are blocked will replace the unreadable file
+                        (a copy is kept as config.json.invalid.bak).
+                    </p>
+                </section>
+            )}
+
+            {rulesError && (
+                <section className="permission-banner warning">
+                    <span>Saved rules could not be loaded</span>
+                    <p className="muted">
+                        {rulesError}. Default rules are active; editing them
+                        will replace the unreadable file (a copy is kept as
+                        rules.json.invalid.bak).
+                    </p>
                 </section>
             )}
 
@@ -316,8 +922,151 @@ function App(): ReactElement {
                 {rules.map((rule, i) => (
                     <div
                         key={rule.id}
-                        className={`rule-row ${isInvalidRule(rule) ? 'rule-invalid' : ''}`}
+                        className={`rule-row ${isInvalidRule(i) ? 'rule-invalid' : ''} ${rule.enabled ? '' : 'rule-disabled'}`}
                     >
+                        <div className="rule-meta">
+                            <input
+                                type="checkbox"
+                                title={
+                                    rule.enabled
+                                        ? 'Disable rule'
+                                        : 'Enable rule'
+                                }
+                                checked={rule.enabled}
+                                disabled={isReadOnly(rule)}
+                                onChange={(): void => {
+                                    updateRule(i, { enabled: !rule.enabled });
+                                }}
+                            />
+                            <input
+                                key={rule.name ?? ''}
+                                className="rule-name"
+                                placeholder="Unnamed rule"
+                                defaultValue={rule.name ?? ''}
+                                title={rule.description ?? undefined}
+                                disabled={isReadOnly(rule)}
+                                onBlur={(e): void => {
+                                    const name = e.target.value.trim() || null;
+                                    if (name !== (rule.name ?? null)) {
+                                        updateRule(i, { name });
+                                    }
+                                }}
+                            />
+                            {rule.schedule && (
+                                <span
+                                    className={
+                                        activeRuleIds.has(rule.id)
+                                            ? 'rule-managed'
+                                            : 'rule-warning-inline'
+                                    }
+                                    title={describeSchedule(rule.schedule)}
+                                >
+                                    {activeRuleIds.has(rule.id)
+                                        ? 'Scheduled · active'
+                                        : 'Scheduled · inactive now'}
+                                </span>
+                            )}
+                            {rule.match_source &&
+                                rule.match_source !== 'immediate' && (
+                                    <span className="rule-managed">
+                                        {rule.match_source === 'original'
+                                            ? 'Original source'
+                                            : 'Original or last source'}
+                                    </span>
+                                )}
+                            {rule.concealed != null && (
+                                <span className="rule-managed">
+                                    {rule.concealed
+                                        ? 'Concealed copies'
+                                        : 'Not concealed'}
+                                </span>
+                            )}
+                            {rule.transient != null && (
+                                <span className="rule-managed">
+                                    {rule.transient
+                                        ? 'Transient copies'
+                                        : 'Not transient'}
+                                </span>
+                            )}
+                            {rule.min_label && (
+                                <span className="rule-managed">
+                                    {rule.min_label}+
+                                </span>
+                            )}
+                            {rule.outside_group && (
+                                <span className="rule-managed">
+                                    Outside {rule.outside_group}
+                                </span>
+                            )}
+                            {rule.max_age != null && (
+                                <span className="rule-managed">
+                                    Copied ≤ {rule.max_age}s ago
+                                </span>
+                            )}
+                            {rule.min_age != null && (
+                                <span className="rule-managed">
+                                    Copied ≥ {rule.min_age}s ago
+                                </span>
+                            )}
+                            {rule.expires_at && (
+                                <span className="rule-managed">
+                                    Expires {formatTime(rule.expires_at)}
+                                </span>
+                            )}
+                            <select
+                                className="rule-snooze"
+                                value=""
+                                disabled={isReadOnly(rule) || !rule.enabled}
+                                onChange={(e): void => {
+                                    const choice =
+                                        SNOOZE_CHOICES[Number(e.target.value)];
+                                    void addOverride(
+                                        {
+                                            kind: 'snooze_rule',
+                                            rule_id: rule.id,
+                                        },
+                                        choice.until(),
+                                    );
+                                }}
+                            >
+                                <option value="" disabled>
+                                    Snooze…
+                                </option>
+                                {SNOOZE_CHOICES.map((c, n) => (
+                                    <option key={c.label} value={n}>
+                                        {c.label}
+                                    </option>
+                                ))}
+                            </select>
+                        </div>
+                        {rule.action === 'sanitize' && (
+                            <div className="rule-transforms">
+                                {TRANSFORMS.map((t) => {
+                                    // Keep at least one; an empty list means the defaults
+                                    const on = transformsOf(rule).includes(
+                                        t.value,
+                                    );
+                                    return (
+                                        <label key={t.value}>
+                                            <input
+                                                type="checkbox"
+                                                checked={on}
+                                                disabled={
+                                                    isReadOnly(rule) ||
+                                                    (on &&
+                                                        transformsOf(rule)
+                                                            .length === 1)
+                                                }
+                                                onChange={(): void => {
+                                                    toggleTransform(i, t.value);
+                                                }}
+                                            />
+                                            {t.label}
+                                        </label>
+                                    );
+                                })}
+                            </div>
+                        )}
                         <div className="rule-fields">
                             <div className="rule-field">
                                 <span className="rule-label">From</span>
@@ -327,6 +1076,7 @@ function App(): ReactElement {
                                 <div className="rule-btns">
                                     <button
                                         type="button"
+                                        disabled={isReadOnly(rule)}
                                         className="btn-browse"
                                         onClick={(): void => {
                                             browseFrom(i);
@@ -337,6 +1087,7 @@ function App(): ReactElement {
                                     {rule.from_app_id && (
                                         <button
                                             type="button"
+                                            disabled={isReadOnly(rule)}
                                             className="btn-clear"
                                             onClick={(): void => {
                                                 clearFrom(i);
@@ -358,6 +1109,7 @@ function App(): ReactElement {
                                 <div className="rule-btns">
                                     <button
                                         type="button"
+                                        disabled={isReadOnly(rule)}
                                         className="btn-browse"
                                         onClick={(): void => {
                                             browseTo(i);
@@ -368,6 +1120,7 @@ function App(): ReactElement {
                                     {rule.to_app_id && (
                                         <button
                                             type="button"
+                                            disabled={isReadOnly(rule)}
                                             className="btn-clear"
                                             onClick={(): void => {
                                                 clearTo(i);
@@ -381,18 +1134,23 @@ function App(): ReactElement {
                         </div>
 
                         <div className="rule-actions">
+                            {rule.managed && (
+                                <span className="rule-managed">Managed</span>
+                            )}
                             <button
                                 type="button"
-                                className={`action-toggle ${rule.action === 'block' ? 'action-block' : 'action-notify'}`}
+                                disabled={isReadOnly(rule)}
+                                className={`action-toggle action-${rule.action}`}
                                 onClick={(): void => {
                                     toggleAction(i);
                                 }}
                             >
-                                {rule.action === 'notify' ? 'Notify' : 'Block'}
+                                {ACTION_LABELS[rule.action]}
                             </button>
 
                             <button
                                 type="button"
+                                disabled={isReadOnly(rule)}
                                 className="btn-remove"
                                 onClick={(): void => {
                                     removeRule(i);
@@ -402,22 +1160,152 @@ function App(): ReactElement {
                             </button>
                         </div>
 
-                        {isInvalidRule(rule) && (
-                            <span className="rule-error">
-                                At least one app must be specified
+                        {diagnosticsFor(i).map((d) => (
+                            <span
+                                key={d.code}
+                                className={
+                                    d.level === 'error'
+                                        ? 'rule-error'
+                                        : 'rule-warning'
+                                }
+                            >
+                                {d.message}
                             </span>
-                        )}
+                        ))}
                     </div>
                 ))}
                 <button
                     type="button"
                     className="btn-add"
+                    disabled={policySettings.lock_user_rules}
                     onClick={addRule}
                 >
                     + Add Rule
                 </button>
+                <div className="rules-io">
+                    <button
+                        type="button"
+                        className="btn-browse"
+                        disabled={policySettings.lock_user_rules}
+                        onClick={(): void => {
+                            void chooseImport();
+                        }}
+                    >
+                        Import…
+                    </button>
+                    <button
+                        type="button"
+                        className="btn-browse"
+                        onClick={(): void => {
+                            void exportRules();
+                        }}
+                    >
+                        Export…
+                    </button>
+                </div>
+                {importError && <p className="rule-error">{importError}</p>}
+                {pendingImport && (
+                    <div className="import-preview">
+                        <div className="row space-between">
+                            <span>Import preview</span>
+                            <button
+                                type="button"
+                                className="btn-refresh"
+                                onClick={(): void => {
+                                    void previewImport(
+                                        pendingImport.path,
+                                        pendingImport.mode === 'merge'
+                                            ? 'replace'
+                                            : 'merge',
+                                    );
+                                }}
+                            >
+                                Mode:{' '}
+                                {pendingImport.mode === 'merge'
+                                    ? 'Merge'
+                                    : 'Replace'}
+                            </button>
+                        </div>
+                        <ul className="import-changes">
+                            {pendingImport.preview.added.map((r, i) => (
+                                <li key={`add-${i}`} className="import-added">
+                                    + {describeRule(r)}
+                                </li>
+                            ))}
+                            {pendingImport.preview.removed.map((r, i) => (
+                                <li
+                                    key={`remove-${i}`}
+                                    className="import-removed"
+                                >
+                                    − {describeRule(r)}
+                                </li>
+                            ))}
+                        </ul>
+                        <p className="muted">
+                            {pendingImport.preview.unchanged} rule(s) unchanged
+                        </p>
+                        {pendingImport.preview.error && (
+                            <p className="rule-error">
+                                {pendingImport.preview.error}
+                            </p>
+                        )}
+                        <div className="permission-actions">
+                            <button
+                                type="button"
+                                className="btn-permission"
+                                disabled={pendingImport.preview.error !== null}
+                                onClick={(): void => {
+                                    void applyImport(pendingImport);
+                                }}
+                            >
+                                Apply
+                            </button>
+                            <button
+                                type="button"
+                                className="btn-refresh"
+                                onClick={(): void => {
+                                    setPendingImport(null);
+                                }}
+                            >
+                                Cancel
+                            </button>
+                        </div>
+                    </div>
+                )}
             </section>
 
+            {(overrides.length > 0 || overrideError) && (
+                <section className="card">
+                    <h2>Temporary Overrides</h2>
+                    {overrideError && (
+                        <p className="rule-error">{overrideError}</p>
+                    )}
+                    <ul className="warning-list">
+                        {overrides.map((o) => (
+                            <li key={o.id} className="row space-between">
+                                <span>
+                                    {describeOverride(o)}
+                                    <span className="muted">
+                                        {' '}
+                                        until {formatTime(o.until)}
+                                    </span>
+                                </span>
+                                <button
+                                    type="button"
+                                    className="btn-remove"
+                                    title="End now"
+                                    onClick={(): void => {
+                                        void removeOverride(o.id);
+                                    }}
+                                >
+                                    ×
+                                </button>
+                            </li>
+                        ))}
+                    </ul>
+                </section>
+            )}
+
             <section className="card">
                 <h2>Last Clipboard Source</h2>
                 {lastSource ? (
@@ -425,6 +1313,33 @@ function App(): ReactElement {
                         {lastSource.source_app_name ??
                             lastSource.source_app_id ??
                             'Unknown'}
+                        {lastSource.origin &&
+                            lastSource.origin.app_id !==
+                                lastSource.source_app_id && (
+                                <span className="muted">
+                                    {' '}
+                                    · first copied from{' '}
+                                    {lastSource.origin.app_name ??
+                                        lastSource.origin.app_id ??
+                                        'an unknown app'}{' '}
+                                    at {formatTime(lastSource.origin.first_seen)}
+                                </span>
+                            )}
+                        {lastSource.copied_at && (
+                            <span className="muted">
+                                {' '}
+                                · {formatTime(lastSource.copied_at)}
+                            </span>
+                        )}
+                        {lastSource.label && (
+                            <span className="muted">
+                                {' '}
+                                · {lastSource.label}
+                            </span>
+                        )}
+                        {lastSource.concealed && (
+                            <span className="muted"> · concealed</span>
+                        )}
                     </p>
                 ) : (
                     <p className="muted">No clipboard activity yet</p>
@@ -444,6 +1359,38 @@ function App(): ReactElement {
                                 </strong>
                                 {' → '}
                                 <strong>{w.dest_app_name ?? 'Terminal'}</strong>
+                                <span className="muted">
+                                    {' '}
+                                    {ruleName(w.rule_id) ??
+                                        w.explanation.reason}
+                                </span>
+                                {/* Overrides relax rules, not trust zones */}
+                                {w.dest_app_id &&
+                                    w.rule_id !== null &&
+                                    !policySettings.lock_user_rules && (
+                                        <button
+                                            type="button"
+                                            className="btn-browse"
+                                            onClick={(): void => {
+                                                void addOverride(
+                                                    {
+                                                        kind: 'allow_pair',
+                                                        from_app_id:
+                                                            w.source_app_id,
+                                                        from_app_name:
+                                                            w.source_app_name,
+                                                        to_app_id:
+                                                            w.dest_app_id ?? '',
+                                                        to_app_name:
+                                                            w.dest_app_name,
+                                                    },
+                                                    Date.now() / 1000 + 10 * 60,
+                                                );
+                                            }}
+                                        >
+                                            Allow 10 min
+                                        </button>
+                                    )}
                             </li>
                         ))}
                     </ul>
@@ -0,0 +1,123 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { type ReactElement, useEffect, useState } from 'react';
+import './App.css';
+
+interface Finding {
+    detector: string;
+    severity: 'low' | 'medium' | 'high';
+    message: string;
+    start: number;
+    end: number;
+}
+
+interface PasteReview {
+    source_app_id: string | null;
+    source_app_name: string | null;
+    dest_app_id: string;
+    dest_app_name: string | null;
+    rule_id: string | null;
+    preview: string;
+    truncated: boolean;
+    findings: Finding[];
+    concealed: boolean;
+}
+
+// Shown in its own always-on-top window while a Confirm rule holds a paste
+function ReviewWindow(): ReactElement {
+    const [review, setReview] = useState<PasteReview | null>(null);
+
+    useEffect((): (() => void) => {
+        const cleanups: (() => void)[] = [];
+
+        void invoke<PasteReview | null>('get_paste_review').then(setReview);
+
+        void listen<PasteReview | null>('paste-review', (e) => {
+            setReview(e.payload);
+        }).then((f) => cleanups.push(f));
+
+        return (): void => {
+            cleanups.forEach((f) => {
+                f();
+            });
+        };
+    }, []);
+
+    function resolve(paste: boolean): void {
+        void invoke('resolve_paste_review', { paste });
+    }
+
+    if (!review) {
+        return (
+            <main className="container">
+                <p className="muted">No paste is waiting for review.</p>
+            </main>
+        );
+    }
+
+    return (
+        <main className="container review">
+            <p>
+                <strong>
+                    {review.source_app_name ??
+                        review.source_app_id ??
+                        'Unknown app'}
+                </strong>{' '}
+                →{' '}
+                <strong>{review.dest_app_name ?? review.dest_app_id}</strong>
+            </p>
+
+            {review.concealed ? (
+                <p className="muted">
+                    The source app marked this as a password, so it isn't
+                    shown or checked.
+                </p>
+            ) : (
+                <pre className="review-preview">
+                    {review.preview || '(no text on the clipboard)'}
+                    {review.truncated && '\n…'}
+                </pre>
+            )}
+
+            {review.findings.length > 0 ? (
+                <ul className="review-findings">
+                    {review.findings.map((f) => (
+                        <li
+                            key={`${f.detector}-${f.start}`}
+                            className={`finding-${f.severity}`}
+                        >
+                            {f.message}
+                        </li>
+                    ))}
+                </ul>
+            ) : (
+                !review.concealed && (
+                    <p className="muted">Nothing suspicious found.</p>
+                )
+            )}
+
+            <div className="review-actions">
+                <button
+                    type="button"
+                    className="btn-off"
+                    onClick={(): void => {
+                        resolve(false);
+                    }}
+                >
+                    Don't paste
+                </button>
+                <button
+                    type="button"
+                    className="btn-on"
+                    onClick={(): void => {
+                        resolve(true);
+                    }}
+                >
+                    Paste
+                </button>
+            </div>
+        </main>
+    );
+}
+
+export { ReviewWindow };
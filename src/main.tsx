@@ -1,12 +1,15 @@
 import { StrictMode } from 'react';
 import ReactDOM from 'react-dom/client';
 import { App } from './App';
+import { ReviewWindow } from './ReviewWindow';
+
+// The review window loads the same bundle with ?view=review
+const isReview =
+    new URLSearchParams(window.location.search).get('view') === 'review';
 
 const root = document.getElementById('root');
 if (root) {
     ReactDOM.createRoot(root).render(
-        <StrictMode>
-            <App />
-        </StrictMode>,
+        <StrictMode>{isReview ? <ReviewWindow /> : <App />}</StrictMode>,
     );
 }